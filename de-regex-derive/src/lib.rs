@@ -0,0 +1,273 @@
+/*!
+Derive macro for [de-regex](https://docs.rs/de-regex).
+
+This crate is not meant to be used directly. Enable the `derive` feature of `de-regex` instead.
+*/
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use regex_syntax::hir::{Hir, HirKind};
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr, Type};
+
+/// Derive `FromStr` and a `from_regex_str` function for a struct based on a regular expression.
+///
+/// The regular expression is set with the `#[regex(pattern = "...")]` attribute.
+/// It is validated at compile time and every field that isn't an `Option` or has a
+/// default value must have a matching named group.
+/// A pattern that exceeds the size limit of the regex crate can only be detected at runtime
+/// and results in `Error::BadRegex`.
+#[proc_macro_derive(FromRegex, attributes(regex))]
+pub fn derive_from_regex(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let pattern = parse_pattern(input)?;
+
+    let hir = regex_syntax::Parser::new()
+        .parse(&pattern.value())
+        .map_err(|err| Error::new(pattern.span(), format!("Invalid regular expression: {}", err)))?;
+
+    let groups = group_names(&hir);
+
+    for (names, span) in required_groups(input)? {
+        if !names.iter().any(|name| groups.contains(name)) {
+            return Err(Error::new(span, format!("Missing named group `{}` in regular expression", names[0])));
+        }
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // FromStr is only implemented for types that don't borrow from the input
+    let mut generics = input.generics.clone();
+    generics.make_where_clause().predicates.push(syn::parse_quote! {
+        #ident #ty_generics: for<'de> ::de_regex::__private::Deserialize<'de>
+    });
+    let (_, _, from_str_where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// Deserialize an input string with the regular expression of this type
+            pub fn from_regex_str<'de>(input: &'de str) -> ::std::result::Result<Self, ::de_regex::Error> where Self: ::de_regex::__private::Deserialize<'de> {
+                // The size of the compiled regex isn't validated at compile time
+                static REGEX: ::std::sync::OnceLock<::std::result::Result<::de_regex::__private::Regex, ::de_regex::__private::RegexError>> = ::std::sync::OnceLock::new();
+
+                let regex = REGEX.get_or_init(|| ::de_regex::__private::Regex::new(#pattern))
+                    .as_ref()
+                    .map_err(|err| ::de_regex::Error::BadRegex(err.clone()))?;
+                ::de_regex::__private::from_str_regex(input, regex)
+            }
+        }
+
+        impl #impl_generics ::std::str::FromStr for #ident #ty_generics #from_str_where_clause {
+            type Err = ::de_regex::Error;
+
+            fn from_str(input: &str) -> ::std::result::Result<Self, Self::Err> {
+                Self::from_regex_str(input)
+            }
+        }
+    })
+}
+
+fn parse_pattern(input: &DeriveInput) -> syn::Result<LitStr> {
+    let mut pattern = None;
+
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("regex")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("pattern") {
+                pattern = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("Unsupported regex attribute"))
+            }
+        })?;
+    }
+
+    pattern.ok_or_else(|| Error::new(Span::call_site(), "Missing attribute #[regex(pattern = \"...\")]"))
+}
+
+/// Returns the names of all named groups in the regular expression
+fn group_names(hir: &Hir) -> Vec<String> {
+    let mut names = vec![];
+    collect_group_names(hir, &mut names);
+    names
+}
+
+fn collect_group_names(hir: &Hir, names: &mut Vec<String>) {
+    match hir.kind() {
+        HirKind::Capture(capture) => {
+            if let Some(name) = &capture.name {
+                names.push(name.to_string());
+            }
+            collect_group_names(&capture.sub, names);
+        }
+        HirKind::Repetition(repetition) => collect_group_names(&repetition.sub, names),
+        HirKind::Concat(hirs) | HirKind::Alternation(hirs) => {
+            for hir in hirs {
+                collect_group_names(hir, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the names of all fields that need a matching group followed by their aliases.
+///
+/// Fields of type `Option` or with serde attributes that might provide a value are skipped.
+fn required_groups(input: &DeriveInput) -> syn::Result<Vec<(Vec<String>, Span)>> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => return Err(Error::new(Span::call_site(), "FromRegex can only be derived for structs")),
+    };
+
+    let fields = match fields {
+        Fields::Named(fields) => &fields.named,
+        _ => return Ok(vec![]),
+    };
+
+    // Renaming all fields or defaults for the whole struct can't be validated reliably
+    for key in serde_keys(&input.attrs)? {
+        if key.0 == "rename_all" || key.0 == "default" {
+            return Ok(vec![]);
+        }
+    }
+
+    let mut groups = vec![];
+
+    'fields: for field in fields {
+        if is_option(&field.ty) {
+            continue;
+        }
+
+        let ident = field.ident.as_ref().expect("Named field");
+        let mut names = vec![ident.to_string().trim_start_matches("r#").to_owned()];
+
+        for (key, value) in serde_keys(&field.attrs)? {
+            match (key.as_str(), value) {
+                ("rename", Some(value)) => names[0] = value,
+                ("alias", Some(value)) => names.push(value),
+                ("default", _) | ("skip", _) | ("skip_deserializing", _) | ("flatten", _) => continue 'fields,
+                _ => {}
+            }
+        }
+
+        groups.push((names, ident.span()));
+    }
+
+    Ok(groups)
+}
+
+/// Returns all keys and optional string values of `#[serde(...)]` attributes.
+///
+/// The value of nested attributes like `rename(deserialize = "...")` is the one used for deserialization.
+fn serde_keys(attrs: &[syn::Attribute]) -> syn::Result<Vec<(String, Option<String>)>> {
+    let mut keys = vec![];
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            let key = meta.path.get_ident().map(|ident| ident.to_string()).unwrap_or_default();
+
+            let value = if meta.input.peek(syn::Token![=]) {
+                match meta.value()?.parse::<syn::Expr>()? {
+                    syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(value), .. }) => Some(value.value()),
+                    _ => None,
+                }
+            } else if meta.input.peek(syn::token::Paren) {
+                let mut value = None;
+                meta.parse_nested_meta(|meta| {
+                    if meta.input.peek(syn::Token![=]) {
+                        let nested = meta.value()?.parse::<LitStr>()?;
+                        if meta.path.is_ident("deserialize") {
+                            value = Some(nested.value());
+                        }
+                    }
+                    Ok(())
+                })?;
+                value
+            } else {
+                None
+            };
+
+            keys.push((key, value));
+            Ok(())
+        })?;
+    }
+
+    Ok(keys)
+}
+
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.path.segments.last().is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn names(pattern: &str) -> Vec<String> {
+        group_names(&regex_syntax::Parser::new().parse(pattern).unwrap())
+    }
+
+    fn required(input: &str) -> Vec<String> {
+        let input: DeriveInput = syn::parse_str(input).unwrap();
+        required_groups(&input).unwrap().into_iter().map(|(names, _)| names.join("|")).collect()
+    }
+
+    #[test]
+    fn test_group_names() {
+        assert_eq!(vec!["width", "height"], names(r"^(?P<width>\d+)x(?P<height>\d+)$"));
+        assert_eq!(vec!["a", "b", "c"], names(r"(?P<a>x(?P<b>y)?)|(?:(?P<c>z))*"));
+        assert!(names(r"^(\d+)x(\d+)$").is_empty());
+    }
+
+    #[test]
+    fn test_required_groups() {
+        assert_eq!(vec!["width", "height"], required("struct Dim { width: u32, height: u32 }"));
+        assert_eq!(vec!["width"], required("struct Dim { width: u32, height: Option<u32> }"));
+        assert_eq!(vec!["w"], required("struct Dim { #[serde(rename = \"w\")] width: u32, #[serde(default)] height: u32 }"));
+        assert_eq!(vec!["type"], required("struct Dim { r#type: u32 }"));
+        assert_eq!(vec!["w"], required("struct Dim { #[serde(rename(deserialize = \"w\", serialize = \"x\"))] width: u32 }"));
+        assert_eq!(vec!["width"], required("struct Dim { #[serde(rename(serialize = \"w\"))] width: u32 }"));
+        assert_eq!(vec!["width|w"], required("struct Dim { #[serde(alias = \"w\")] width: u32 }"));
+        assert!(required("#[serde(rename_all = \"UPPERCASE\")] struct Dim { width: u32 }").is_empty());
+        assert!(required("struct Dim(u32, u32);").is_empty());
+    }
+
+    #[test]
+    fn test_missing_group() {
+        let input: DeriveInput = syn::parse_str(r#"
+            #[regex(pattern = r"^(?P<width>\d+)$")]
+            struct Dim { width: u32, height: u32 }
+        "#).unwrap();
+
+        let err = expand(&input).unwrap_err();
+        assert_eq!("Missing named group `height` in regular expression", err.to_string());
+
+        let input: DeriveInput = syn::parse_str(r#"
+            #[regex(pattern = r"^(?P<w>\d+)x(?P<h>\d+)$")]
+            struct Dim { #[serde(rename(deserialize = "w"))] width: u32, #[serde(alias = "h")] height: u32 }
+        "#).unwrap();
+
+        assert!(expand(&input).is_ok());
+    }
+
+    #[test]
+    fn test_bad_pattern() {
+        let input: DeriveInput = syn::parse_str(r#"
+            #[regex(pattern = r"^(?P<width\d+)$")]
+            struct Dim { width: u32 }
+        "#).unwrap();
+
+        assert!(expand(&input).is_err());
+
+        let input: DeriveInput = syn::parse_str("struct Dim { width: u32 }").unwrap();
+        assert!(expand(&input).is_err());
+    }
+}
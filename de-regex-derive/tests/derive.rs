@@ -0,0 +1,74 @@
+use serde::Deserialize;
+use de_regex::FromRegex;
+
+#[derive(Deserialize, FromRegex, PartialEq, Debug)]
+#[regex(pattern = r"^(?P<width>\d+)x(?P<height>\d+)(?:@(?P<dpi>\d+))?$")]
+struct Dimension {
+    width: u32,
+    height: u32,
+    dpi: Option<u32>,
+}
+
+#[derive(Deserialize, FromRegex, PartialEq, Debug)]
+#[regex(pattern = r"^(?P<key>\w+)=(?P<value>\w*)$")]
+struct Entry {
+    key: String,
+    #[serde(rename = "value")]
+    v: String,
+}
+
+#[derive(Deserialize, FromRegex, PartialEq, Debug)]
+#[regex(pattern = r"^(?P<w>\d+)x(?P<h>\d+)$")]
+struct Short {
+    #[serde(rename(deserialize = "w"))]
+    width: u32,
+    #[serde(alias = "h")]
+    height: u32,
+}
+
+#[derive(Deserialize, FromRegex, PartialEq, Debug)]
+#[regex(pattern = r"^(?P<a>\w{1000}){100}$")]
+struct Big {
+    a: String,
+}
+
+#[derive(Deserialize, FromRegex, PartialEq, Debug)]
+#[regex(pattern = r"^(?P<key>\w+)=(?P<value>\w*)$")]
+struct Borrowed<'a> {
+    key: &'a str,
+    value: &'a str,
+}
+
+#[test]
+fn test_from_str() {
+    let dim: Dimension = "800x600".parse().unwrap();
+    assert_eq!(Dimension { width: 800, height: 600, dpi: None }, dim);
+
+    let dim: Dimension = "800x600@96".parse().unwrap();
+    assert_eq!(Dimension { width: 800, height: 600, dpi: Some(96) }, dim);
+
+    assert!("800-600".parse::<Dimension>().is_err());
+}
+
+#[test]
+fn test_from_regex_str() {
+    assert_eq!(Dimension { width: 800, height: 600, dpi: None }, Dimension::from_regex_str("800x600").unwrap());
+    assert!(matches!(Dimension::from_regex_str("800-600"), Err(de_regex::Error::NoMatch { .. })));
+    assert!(matches!("x".parse::<Big>(), Err(de_regex::Error::BadRegex(_))));
+    assert!(matches!(Big::from_regex_str("x"), Err(de_regex::Error::BadRegex(_))));
+}
+
+#[test]
+fn test_rename() {
+    let entry: Entry = "foo=bar".parse().unwrap();
+    assert_eq!(Entry { key: "foo".to_owned(), v: "bar".to_owned() }, entry);
+
+    let short: Short = "800x600".parse().unwrap();
+    assert_eq!(Short { width: 800, height: 600 }, short);
+}
+
+#[test]
+fn test_borrowed() {
+    let input = String::from("foo=bar");
+    assert_eq!(Borrowed { key: "foo", value: "bar" }, Borrowed::from_regex_str(&input).unwrap());
+}
@@ -0,0 +1,333 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::de::{DeserializeSeed, Deserializer, IntoDeserializer, SeqAccess, Visitor};
+use serde::Deserialize;
+
+use crate::error::Error;
+
+// Name of the newtype struct used to recognize amounts in the value deserializer
+pub(crate) const AMOUNT_TOKEN: &str = "$de_regex::Amount";
+
+/// Position of the currency symbol relative to the number
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SymbolPosition {
+    /// The symbol precedes the number like in `$1,234.56`
+    Prefix,
+    /// The symbol follows the number like in `1.234,56 €`
+    Suffix,
+    /// The symbol can be on either side
+    Any,
+}
+
+/// The format of an [Amount] set with [Builder::amount_format](crate::Builder::amount_format)
+///
+/// # Example
+/// ```rust
+/// # fn main() -> Result<(), de_regex::Error> {
+/// use serde::Deserialize;
+/// use de_regex::{Amount, AmountFormat, Builder, Pattern, SymbolPosition};
+///
+/// #[derive(Deserialize)]
+/// struct Invoice {
+///     total: Amount,
+/// }
+///
+/// let pattern: Pattern<Invoice> = Builder::new(r"^Total: (?P<total>.*)$")
+///     .amount_format(AmountFormat {
+///         decimal_separator: ',',
+///         group_separator: Some('.'),
+///         symbol_position: SymbolPosition::Suffix,
+///     })
+///     .build()?;
+///
+/// let invoice = pattern.from_str("Total: 1.234,56 €")?;
+///
+/// assert_eq!(invoice.total.mantissa(), 123456);
+/// assert_eq!(invoice.total.scale(), 2);
+/// assert_eq!(invoice.total.currency(), Some("EUR"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AmountFormat {
+    /// Separator of the fractional part
+    pub decimal_separator: char,
+    /// Optional separator of digit groups
+    pub group_separator: Option<char>,
+    /// Allowed position of the currency symbol
+    pub symbol_position: SymbolPosition,
+}
+
+impl Default for AmountFormat {
+    fn default() -> Self {
+        AmountFormat {
+            decimal_separator: '.',
+            group_separator: Some(','),
+            symbol_position: SymbolPosition::Any,
+        }
+    }
+}
+
+/// A decimal amount of money with an optional currency code.
+///
+/// The value is stored as an integer `mantissa` scaled by `10^-scale` to avoid rounding errors.
+/// Currency symbols like `$` or `€` are converted into their ISO 4217 code.
+/// Three letter codes like `CHF` are accepted as well.
+///
+/// The default format uses `.` as decimal separator and `,` to separate digit groups.
+/// Other formats can be set with [Builder::amount_format](crate::Builder::amount_format).
+///
+/// # Example
+/// ```rust
+/// # fn main() -> Result<(), de_regex::Error> {
+/// use serde::Deserialize;
+/// use de_regex::Amount;
+///
+/// #[derive(Deserialize)]
+/// struct Invoice {
+///     total: Amount,
+/// }
+///
+/// let invoice: Invoice = de_regex::from_str("Total: $1,234.56", r"^Total: (?P<total>.*)$")?;
+///
+/// assert_eq!(invoice.total.mantissa(), 123456);
+/// assert_eq!(invoice.total.scale(), 2);
+/// assert_eq!(invoice.total.currency(), Some("USD"));
+/// assert_eq!(invoice.total.to_string(), "1234.56 USD");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Amount {
+    mantissa: i64,
+    scale: u32,
+    currency: Option<String>,
+}
+
+impl Amount {
+    /// Create a new amount with the value `mantissa * 10^-scale`
+    pub fn new(mantissa: i64, scale: u32, currency: Option<&str>) -> Amount {
+        Amount {
+            mantissa,
+            scale,
+            currency: currency.map(str::to_owned),
+        }
+    }
+
+    /// Parse an amount with the given format
+    pub fn parse(value: &str, format: &AmountFormat) -> Option<Amount> {
+        let mut value = value.trim();
+        let mut negative = false;
+
+        // The sign might either precede the symbol or the number
+        if let Some(rest) = value.strip_prefix('-') {
+            negative = true;
+            value = rest.trim_start();
+        }
+
+        let start = value.find(|c: char| c.is_ascii_digit() || c == '-' || c == format.decimal_separator)?;
+        let end = value.rfind(|c: char| c.is_ascii_digit())? + 1;
+
+        let prefix = value[..start].trim();
+        let suffix = value[end..].trim();
+        let mut number = value.get(start..end)?;
+
+        if let Some(rest) = number.strip_prefix('-') {
+            if negative {
+                return None;
+            }
+
+            negative = true;
+            number = rest;
+        }
+
+        let symbol = match (prefix.is_empty(), suffix.is_empty(), format.symbol_position) {
+            (true, true, _) => None,
+            (false, true, SymbolPosition::Prefix) | (false, true, SymbolPosition::Any) => Some(prefix),
+            (true, false, SymbolPosition::Suffix) | (true, false, SymbolPosition::Any) => Some(suffix),
+            _ => return None,
+        };
+
+        let currency = match symbol {
+            Some(symbol) => Some(currency_code(symbol)?),
+            None => None,
+        };
+
+        let mut mantissa: i64 = 0;
+        let mut scale = None;
+        let mut digits = 0;
+
+        // Digits since the last group separator, which must always be followed by exactly three digits
+        let mut group = 0;
+        let mut grouped = false;
+
+        for c in number.chars() {
+            if let Some(digit) = c.to_digit(10) {
+                // Negative values are accumulated as such to support i64::MIN
+                let digit = if negative { -(digit as i64) } else { digit as i64 };
+                mantissa = mantissa.checked_mul(10)?.checked_add(digit)?;
+                scale = scale.map(|scale| scale + 1);
+                digits += 1;
+                group += 1;
+            } else if c == format.decimal_separator && scale.is_none() && (!grouped || group == 3) {
+                scale = Some(0);
+            } else if Some(c) == format.group_separator && scale.is_none() && group > 0 && group <= 3 && (!grouped || group == 3) {
+                grouped = true;
+                group = 0;
+            } else {
+                return None;
+            }
+        }
+
+        if digits == 0 || (grouped && scale.is_none() && group != 3) {
+            return None;
+        }
+
+        Some(Amount {
+            mantissa,
+            scale: scale.unwrap_or(0),
+            currency: currency.map(str::to_owned),
+        })
+    }
+
+    /// Returns the unscaled integer value
+    pub fn mantissa(&self) -> i64 {
+        self.mantissa
+    }
+
+    /// Returns the number of digits of the fractional part
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Returns the ISO 4217 currency code if a currency was given
+    pub fn currency(&self) -> Option<&str> {
+        self.currency.as_deref()
+    }
+
+    /// Returns the value as floating point number
+    pub fn to_f64(&self) -> f64 {
+        self.mantissa as f64 / 10f64.powi(self.scale as i32)
+    }
+}
+
+fn currency_code(symbol: &str) -> Option<&str> {
+    let code = match symbol {
+        "$" | "US$" => "USD",
+        "€" => "EUR",
+        "£" => "GBP",
+        "¥" => "JPY",
+        "₹" => "INR",
+        "₩" => "KRW",
+        "₽" => "RUB",
+        code if code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase()) => code,
+        _ => return None,
+    };
+
+    Some(code)
+}
+
+impl Display for Amount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (int, frac) = digits.split_at(digits.len() - scale);
+
+        if self.mantissa < 0 {
+            write!(f, "-")?;
+        }
+
+        write!(f, "{}", int)?;
+
+        if !frac.is_empty() {
+            write!(f, ".{}", frac)?;
+        }
+
+        if let Some(currency) = &self.currency {
+            write!(f, " {}", currency)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Amount {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Amount::parse(s, &AmountFormat::default()).ok_or_else(|| Error::Custom(format!("Invalid amount: {}", s)))
+    }
+}
+
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        deserializer.deserialize_newtype_struct(AMOUNT_TOKEN, AmountVisitor)
+    }
+}
+
+struct AmountVisitor;
+
+impl<'de> Visitor<'de> for AmountVisitor {
+    type Value = Amount;
+
+    fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "an amount of money")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> where E: serde::de::Error {
+        Amount::parse(v, &AmountFormat::default()).ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(v), &self))
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error> where D: Deserializer<'de> {
+        deserializer.deserialize_str(self)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error> where A: SeqAccess<'de> {
+        use serde::de::Error;
+
+        let mantissa = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        let scale = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(1, &self))?;
+        let currency = seq.next_element()?;
+
+        Ok(Amount {
+            mantissa,
+            scale,
+            currency,
+        })
+    }
+}
+
+/// The parts of an already parsed [Amount] that are passed to the visitor as sequence
+pub(crate) struct AmountParts {
+    amount: Amount,
+    index: usize,
+}
+
+impl AmountParts {
+    pub fn new(amount: Amount) -> AmountParts {
+        AmountParts {
+            amount,
+            index: 0,
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for AmountParts {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error> where T: DeserializeSeed<'de> {
+        self.index += 1;
+
+        match self.index {
+            1 => seed.deserialize(self.amount.mantissa.into_deserializer()).map(Some),
+            2 => seed.deserialize(self.amount.scale.into_deserializer()).map(Some),
+            3 => match self.amount.currency.take() {
+                Some(currency) => seed.deserialize(currency.into_deserializer()).map(Some),
+                None => Ok(None),
+            },
+            _ => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,364 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use regex::{Regex, RegexBuilder};
+use regex_automata::nfa::thompson;
+
+use crate::amount::AmountFormat;
+use crate::error::*;
+use crate::pattern::Pattern;
+use crate::timeout::TimedRegex;
+
+/// Options shared by all deserializations of a [Pattern]
+#[derive(Clone, Default)]
+pub(crate) struct Options {
+    pub id: Option<String>,
+    pub timeout: Option<(Duration, Arc<TimedRegex>)>,
+    pub slow_match: Option<(Duration, SlowMatchHook)>,
+    pub amount_format: AmountFormat,
+    pub true_tokens: Vec<String>,
+    pub false_tokens: Vec<String>,
+    pub separators: HashMap<String, Regex>,
+    pub radix_prefixes: bool,
+    pub trim: bool,
+    pub empty_as_default: bool,
+    pub lossy_utf8: bool,
+}
+
+type SlowMatchHook = Arc<dyn Fn(&SlowMatch) + Send + Sync>;
+
+/// Information about a single slow match passed to the hook set with [Builder::slow_match]
+#[derive(Debug)]
+pub struct SlowMatch<'a> {
+    pub(crate) id: &'a str,
+    pub(crate) input_len: usize,
+    pub(crate) elapsed: Duration,
+}
+
+impl<'a> SlowMatch<'a> {
+    /// Returns the id of the pattern set with [Builder::id] or the regular expression itself
+    pub fn id(&self) -> &'a str {
+        self.id
+    }
+
+    /// Returns the length of the input in bytes
+    pub fn input_len(&self) -> usize {
+        self.input_len
+    }
+
+    /// Returns the duration of the regex search
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+/// A builder to compile a [Pattern] with non default options.
+///
+/// # Example
+/// ```rust
+/// # fn main() -> Result<(), de_regex::Error> {
+/// use std::time::Duration;
+/// use serde::Deserialize;
+/// use de_regex::{Builder, Pattern};
+///
+/// #[derive(Deserialize)]
+/// struct Dimension {
+///     width: u32,
+///     height: u32
+/// }
+///
+/// let pattern: Pattern<Dimension> = Builder::new(r"^(?P<width>\d+)x(?P<height>\d+)$")
+///     .timeout(Duration::from_millis(10))
+///     .build()?;
+///
+/// let dim = pattern.from_str("800x600")?;
+///
+/// assert_eq!(dim.width, 800);
+/// assert_eq!(dim.height, 600);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Builder {
+    pattern: String,
+    rest: Option<String>,
+    separators: Vec<(String, String)>,
+    size_limit: Option<usize>,
+    timeout: Option<Duration>,
+    options: Options,
+}
+
+impl Builder {
+    /// Create a new builder for the given regular expression
+    pub fn new(pattern: &str) -> Builder {
+        Builder {
+            pattern: pattern.to_owned(),
+            rest: None,
+            separators: vec![],
+            size_limit: None,
+            timeout: None,
+            options: Options::default(),
+        }
+    }
+
+    /// Set an id to identify the pattern in a [SlowMatch].
+    ///
+    /// If no id is set the regular expression itself is used.
+    pub fn id(&mut self, id: &str) -> &mut Builder {
+        self.options.id = Some(id.to_owned());
+        self
+    }
+
+    /// Set a time budget for every single deserialization.
+    ///
+    /// If the budget is exceeded deserialization stops with [Error::Timeout].
+    /// The input is searched in chunks of a few kilobytes with a lazy DFA and the budget is checked
+    /// between two chunks and before every field is converted. After a match was found its groups
+    /// are extracted from the matched text in chunks as well, which is slower than an unlimited search.
+    ///
+    /// Patterns with Unicode word boundaries can't be searched this way and are rejected by
+    /// [build](Builder::build) with [Error::TimeoutUnsupported]. Use ASCII word boundaries like `(?-u:\b)` instead.
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Builder {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Call `hook` whenever a single regex search takes longer than `threshold`.
+    ///
+    /// This helps to surface pathological combinations of patterns and inputs, e.g. by logging them.
+    ///
+    /// # Example
+    /// ```rust
+    /// # fn main() -> Result<(), de_regex::Error> {
+    /// use std::time::Duration;
+    /// use std::collections::HashMap;
+    /// use de_regex::{Builder, Pattern};
+    ///
+    /// let pattern: Pattern<HashMap<String, String>> = Builder::new(r"^(?P<key>\w+)=(?P<value>\w+)$")
+    ///     .id("key-value")
+    ///     .slow_match(Duration::from_millis(10), |m| {
+    ///         eprintln!("Slow match of {} on {} bytes: {:?}", m.id(), m.input_len(), m.elapsed());
+    ///     })
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn slow_match<F>(&mut self, threshold: Duration, hook: F) -> &mut Builder where F: Fn(&SlowMatch) + Send + Sync + 'static {
+        self.options.slow_match = Some((threshold, Arc::new(hook)));
+        self
+    }
+
+    /// Accept additional tokens for `bool` values.
+    ///
+    /// The tokens are compared case insensitive like the default tokens `true` and `false` that are always accepted.
+    ///
+    /// # Example
+    /// ```rust
+    /// # fn main() -> Result<(), de_regex::Error> {
+    /// use serde::Deserialize;
+    /// use de_regex::{Builder, Pattern};
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Task {
+    ///     done: bool,
+    ///     name: String,
+    /// }
+    ///
+    /// let pattern: Pattern<Task> = Builder::new(r"^- (?P<done>\[[ x]\]) (?P<name>.*)$")
+    ///     .bool_tokens(&["[x]"], &["[ ]"])
+    ///     .build()?;
+    ///
+    /// assert!(pattern.from_str("- [x] Write docs")?.done);
+    /// assert!(!pattern.from_str("- [ ] Write tests")?.done);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bool_tokens(&mut self, true_tokens: &[&str], false_tokens: &[&str]) -> &mut Builder {
+        self.options.true_tokens = true_tokens.iter().map(|&token| token.to_owned()).collect();
+        self.options.false_tokens = false_tokens.iter().map(|&token| token.to_owned()).collect();
+        self
+    }
+
+    /// Parse integers with a `0x`, `0o` or `0b` prefix as hexadecimal, octal or binary values
+    pub fn radix_prefixes(&mut self, enabled: bool) -> &mut Builder {
+        self.options.radix_prefixes = enabled;
+        self
+    }
+
+    /// Remove leading and trailing whitespace from all captured values
+    pub fn trim(&mut self, enabled: bool) -> &mut Builder {
+        self.options.trim = enabled;
+        self
+    }
+
+    /// Treat empty captures like missing groups.
+    ///
+    /// This allows serde to use the default value of fields marked with `#[serde(default)]`
+    /// instead of failing to convert an empty string.
+    ///
+    /// # Example
+    /// ```rust
+    /// # fn main() -> Result<(), de_regex::Error> {
+    /// use serde::Deserialize;
+    /// use de_regex::{Builder, Pattern};
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Config {
+    ///     name: String,
+    ///     #[serde(default)]
+    ///     port: u16,
+    /// }
+    ///
+    /// let pattern: Pattern<Config> = Builder::new(r"^(?P<name>\w+):(?P<port>\d*)$")
+    ///     .empty_as_default(true)
+    ///     .build()?;
+    ///
+    /// assert_eq!(pattern.from_str("localhost:")?.port, 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn empty_as_default(&mut self, enabled: bool) -> &mut Builder {
+        self.options.empty_as_default = enabled;
+        self
+    }
+
+    /// Set the format used to parse [Amount](crate::Amount) values
+    pub fn amount_format(&mut self, format: AmountFormat) -> &mut Builder {
+        self.options.amount_format = format;
+        self
+    }
+
+    /// Capture everything after the match of the pattern up to the end of the line into the group `name`.
+    ///
+    /// This is done by appending the group `(?P<name>.*)` to the pattern. Patterns anchored
+    /// at the end with `$` will therefore always capture an empty string.
+    ///
+    /// # Example
+    /// ```rust
+    /// # fn main() -> Result<(), de_regex::Error> {
+    /// use serde::Deserialize;
+    /// use de_regex::{Builder, Pattern};
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Line {
+    ///     level: String,
+    ///     message: String,
+    /// }
+    ///
+    /// let pattern: Pattern<Line> = Builder::new(r"^\[(?P<level>\w+)\] ")
+    ///     .rest("message")
+    ///     .build()?;
+    ///
+    /// let line = pattern.from_str("[INFO] Server started on port 8080")?;
+    ///
+    /// assert_eq!(line.level, "INFO");
+    /// assert_eq!(line.message, "Server started on port 8080");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn rest(&mut self, name: &str) -> &mut Builder {
+        self.rest = Some(name.to_owned());
+        self
+    }
+
+    /// Split the value of the group `name` with the regular expression `separator` to deserialize it into a sequence like `Vec<T>`.
+    ///
+    /// An empty value results in an empty sequence.
+    /// An invalid separator is reported by [build](Builder::build).
+    ///
+    /// # Example
+    /// ```rust
+    /// # fn main() -> Result<(), de_regex::Error> {
+    /// use serde::Deserialize;
+    /// use de_regex::{Builder, Pattern};
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Series {
+    ///     name: String,
+    ///     values: Vec<u32>,
+    /// }
+    ///
+    /// let pattern: Pattern<Series> = Builder::new(r"^(?P<name>\w+): (?P<values>(\d+,\s*)*\d+)$")
+    ///     .split_field("values", r",\s*")
+    ///     .build()?;
+    ///
+    /// let series = pattern.from_str("primes: 2, 3,5,  7")?;
+    ///
+    /// assert_eq!(series.values, vec![2, 3, 5, 7]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn split_field(&mut self, name: &str, separator: &str) -> &mut Builder {
+        self.separators.push((name.to_owned(), separator.to_owned()));
+        self
+    }
+
+    /// Set the maximum size in bytes of the compiled program.
+    ///
+    /// Patterns exceeding this limit are rejected by [build](Builder::build) with [Error::PatternTooBig].
+    /// The size of an accepted pattern is available with [Pattern::compiled_size].
+    ///
+    /// To measure the size every pattern is compiled twice, once into an NFA and once into the final regex.
+    pub fn size_limit(&mut self, bytes: usize) -> &mut Builder {
+        self.size_limit = Some(bytes);
+        self
+    }
+
+    /// Replace the regular expression, e.g. to compile another pattern with the same options
+    pub(crate) fn pattern(&mut self, pattern: &str) -> &mut Builder {
+        self.pattern = pattern.to_owned();
+        self
+    }
+
+    /// Compile the regular expression and return a reusable [Pattern]
+    pub fn build<T>(&self) -> std::result::Result<Pattern<T>, Error> {
+        let pattern = match &self.rest {
+            Some(name) => format!("(?:{})(?P<{}>.*)", self.pattern, name),
+            None => self.pattern.clone(),
+        };
+
+        // The regex crate has its own default limit that must not be hit before ours
+        let hard_limit = self.size_limit.map_or(DEFAULT_REGEX_SIZE_LIMIT, |limit| limit.max(DEFAULT_REGEX_SIZE_LIMIT));
+
+        // Oversized patterns are rejected before the full regex gets compiled.
+        // Patterns exceeding the hard limit exceed the limit set by the user too.
+        let size = compiled_size(&pattern, hard_limit);
+        if let Some(limit) = self.size_limit {
+            match &size {
+                Ok(size) if *size > limit => return Err(Error::PatternTooBig { limit }),
+                Err(err) if err.size_limit().is_some() => return Err(Error::PatternTooBig { limit }),
+                _ => {}
+            }
+        }
+
+        // Syntax errors are left to the regex crate for better error messages
+        let regex = RegexBuilder::new(&pattern)
+            .size_limit(hard_limit)
+            .build()
+            .map_err(Error::BadRegex)?;
+
+        let size = size.map_err(|err| Error::Custom(err.to_string()))?;
+
+        let mut options = self.options.clone();
+        if let Some(timeout) = self.timeout {
+            options.timeout = Some((timeout, Arc::new(TimedRegex::new(&pattern, hard_limit)?)));
+        }
+
+        for (name, separator) in &self.separators {
+            options.separators.insert(name.clone(), Regex::new(separator).map_err(Error::BadRegex)?);
+        }
+
+        Ok(Pattern::with_options(regex, size, options))
+    }
+}
+
+const DEFAULT_REGEX_SIZE_LIMIT: usize = 10 * (1 << 20);
+
+fn compiled_size(pattern: &str, hard_limit: usize) -> std::result::Result<usize, Box<thompson::BuildError>> {
+    thompson::Compiler::new()
+        .configure(thompson::Config::new().nfa_size_limit(Some(hard_limit)))
+        .build(pattern)
+        .map(|nfa| nfa.memory_usage())
+        .map_err(Box::new)
+}
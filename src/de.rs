@@ -1,27 +1,117 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt::Debug;
+use std::ops::Range;
 use std::str::FromStr;
+use std::time::Instant;
 
 use serde::de::{Visitor, IntoDeserializer};
-use serde::de::value::MapDeserializer;
+use serde::de::value::{MapDeserializer, SeqDeserializer, BorrowedStrDeserializer, StringDeserializer};
 
 use regex::Regex;
+use regex::bytes::Regex as BytesRegex;
 
+use crate::builder::{Options, SlowMatch};
 use crate::error::*;
+use crate::render::Trace;
+use crate::amount::{Amount, AmountParts, AMOUNT_TOKEN};
 
-pub(crate) struct Deserializer<'de> {
+/// Byte offsets of all groups of a single match
+pub(crate) type Locations = Vec<Option<(usize, usize)>>;
+
+pub(crate) struct Deserializer<'a, 'de> {
     input: &'de str,
-    regex: Regex,
+    regex: &'a Regex,
+    captures: Option<Locations>,
+    options: &'a Options,
+    deadline: Option<Instant>,
+    trace: Option<&'a Trace>,
 }
 
-impl<'de> Deserializer<'de> {
-    pub fn new(input: &'de str, regex: Regex) -> Deserializer {
+impl<'a, 'de> Deserializer<'a, 'de> {
+    pub fn new(input: &'de str, regex: &'a Regex, options: &'a Options) -> Deserializer<'a, 'de> {
         Deserializer {
             input,
             regex,
+            captures: None,
+            options,
+            deadline: options.timeout.as_ref().map(|(timeout, _)| Instant::now() + *timeout),
+            trace: None,
+        }
+    }
+
+    /// Match the input in advance and return false if it doesn't match
+    pub fn try_match(&mut self) -> Result<bool> {
+        self.try_match_at(0).map(|span| span.is_some())
+    }
+
+    /// Match the input in advance starting at byte offset `start` and return the span of the match
+    pub fn try_match_at(&mut self, start: usize) -> Result<Option<(usize, usize)>> {
+        self.captures = self.search_at(start)?;
+        Ok(self.captures.as_ref().and_then(|captures| captures[0]))
+    }
+
+    /// Use captures of a previous match of the same input instead of matching the input again
+    pub fn with_captures(mut self, captures: Locations) -> Deserializer<'a, 'de> {
+        self.captures = Some(captures);
+        self
+    }
+
+    /// Returns the captures of [try_match](Deserializer::try_match)
+    pub fn into_captures(self) -> Option<Locations> {
+        self.captures
+    }
+
+    fn search_at(&self, start: usize) -> Result<Option<Locations>> {
+        let now = Instant::now();
+
+        // A search with a time budget is interrupted as soon as the deadline is exceeded
+        let captures = match (&self.options.timeout, self.deadline) {
+            (Some((_, regex)), Some(deadline)) => regex.captures_at(self.input, start, deadline),
+            _ => {
+                let mut captures = self.regex.capture_locations();
+                let matched = self.regex.captures_read_at(&mut captures, self.input, start).is_some();
+                Ok(matched.then(|| (0..captures.len()).map(|i| captures.get(i)).collect()))
+            }
+        };
+
+        report_slow_match(self.options, self.regex.as_str(), self.input.len(), now);
+        captures
+    }
+
+    fn take_captures(&mut self, fields: &[&str]) -> Result<Locations> {
+        let caps = match self.captures.take() {
+            Some(caps) => caps,
+            None => self.search_at(0)?.ok_or_else(|| Error::no_match(self.regex.capture_names(), fields))?,
+        };
+
+        check_deadline(self.deadline)?;
+        Ok(caps)
+    }
+
+    fn value<'n>(&self, name: Cow<'n, str>, span: Option<(usize, usize)>) -> Value<'n, 'de> where 'a: 'n {
+        let raw = span.map_or("", |(start, end)| &self.input[start..end]);
+        let value = trim(raw, self.options.trim);
+        let span = span.map(|(start, end)| start..end);
+
+        Value {
+            name,
+            value: Cow::Borrowed(value),
+            span: sub_span(&span, raw.as_bytes(), value.as_bytes()),
+            options: self.options,
+            deadline: self.deadline,
+            trace: self.trace,
         }
     }
+
+    /// Record the conversion of every value into the trace
+    pub fn with_trace(mut self, trace: &'a Trace) -> Deserializer<'a, 'de> {
+        self.trace = Some(trace);
+        self
+    }
 }
 
-impl<'de, 'a> serde::Deserializer<'de> for &'a mut Deserializer<'de> {
+impl<'de> serde::Deserializer<'de> for &mut Deserializer<'_, 'de> {
     type Error = Error;
 
     fn deserialize_any<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
@@ -29,50 +119,270 @@ impl<'de, 'a> serde::Deserializer<'de> for &'a mut Deserializer<'de> {
     }
 
     fn deserialize_map<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
-        let caps = self.regex.captures(&self.input).ok_or_else(Error::NoMatch)?;
+        self.deserialize_struct("", &[], visitor)
+    }
+
+    fn deserialize_struct<V>(self, _name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        let caps = self.take_captures(fields)?;
 
-        let items = self.regex.capture_names().filter_map(|n| {
-            n.and_then(|name| caps.name(name).map(|value| {
-                (name.to_owned(), Value { name: name.to_owned(), value: value.as_str().to_owned() })
+        let items = self.regex.capture_names().enumerate().filter_map(|(i, n)| {
+            n.and_then(|name| caps[i].map(|span| {
+                (name, self.value(Cow::Borrowed(name), Some(span)))
             }))
         });
 
+        // Missing values are replaced by serde with the default value of the field
+        let empty_as_default = self.options.empty_as_default;
+        let items = items.filter(|(_, value)| !(empty_as_default && value.value.is_empty()));
+
         let ms = MapDeserializer::new(items);
 
         visitor.visit_map(ms)
     }
 
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        let caps = self.take_captures(&[])?;
+
+        // Groups that didn't participate in the match are passed as empty strings to support optional values
+        let items = self.regex.capture_names().enumerate().skip(1).map(|(i, n)| {
+            let name = n.map_or_else(|| Cow::Owned(i.to_string()), Cow::Borrowed);
+            self.value(name, caps[i])
+        });
+
+        let mut seq = SeqDeserializer::new(items);
+        let value = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.deserialize_seq(visitor)
+    }
+
     serde::forward_to_deserialize_any! {
         bool
         u8 u16 u32 u64
         i8 i16 i32 i64
         f32 f64
         char str string identifier
-        unit seq bytes byte_buf unit_struct tuple_struct
-        tuple ignored_any option newtype_struct enum struct
+        unit bytes byte_buf unit_struct
+        ignored_any option newtype_struct enum
     }
 }
 
+/// Deserializer for byte inputs matched with [regex::bytes::Regex]
+pub(crate) struct BytesDeserializer<'a, 'de> {
+    input: &'de [u8],
+    regex: &'a BytesRegex,
+    options: &'a Options,
+    deadline: Option<Instant>,
+}
+
+impl<'a, 'de> BytesDeserializer<'a, 'de> {
+    pub fn new(input: &'de [u8], regex: &'a BytesRegex, options: &'a Options) -> BytesDeserializer<'a, 'de> {
+        BytesDeserializer {
+            input,
+            regex,
+            options,
+            deadline: options.timeout.as_ref().map(|(timeout, _)| Instant::now() + *timeout),
+        }
+    }
+
+    fn captures(&self, fields: &[&str]) -> Result<regex::bytes::CaptureLocations> {
+        let mut captures = self.regex.capture_locations();
+
+        let now = Instant::now();
+        let matched = self.regex.captures_read(&mut captures, self.input).is_some();
+        report_slow_match(self.options, self.regex.as_str(), self.input.len(), now);
+
+        if !matched {
+            return Err(Error::no_match(self.regex.capture_names(), fields));
+        }
+
+        check_deadline(self.deadline)?;
+        Ok(captures)
+    }
 
-struct Value {
-    name: String,
-    value: String,
+    fn value<'n>(&self, name: Cow<'n, str>, span: Option<(usize, usize)>) -> BytesValue<'n, 'de> where 'a: 'n {
+        let raw = span.map_or(&[][..], |(start, end)| &self.input[start..end]);
+        let value = if self.options.trim { raw.trim_ascii() } else { raw };
+        let span = span.map(|(start, end)| start..end);
+
+        BytesValue {
+            name,
+            value,
+            span: sub_span(&span, raw, value),
+            options: self.options,
+            deadline: self.deadline,
+        }
+    }
 }
 
-impl Value {
-    fn parse<T>(&self) -> Result<T> where T: FromStr {
-        self.value.parse().map_err(|_| self.get_parse_error())
+impl<'de> serde::Deserializer<'de> for &mut BytesDeserializer<'_, 'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.deserialize_struct("", &[], visitor)
     }
 
-    fn get_parse_error(&self) -> Error {
+    fn deserialize_struct<V>(self, _name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        let caps = self.captures(fields)?;
+
+        let items = self.regex.capture_names().enumerate().filter_map(|(i, n)| {
+            n.and_then(|name| caps.get(i).map(|span| {
+                (name, self.value(Cow::Borrowed(name), Some(span)))
+            }))
+        });
+
+        let empty_as_default = self.options.empty_as_default;
+        let items = items.filter(|(_, value)| !(empty_as_default && value.value.is_empty()));
+
+        visitor.visit_map(MapDeserializer::new(items))
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        let caps = self.captures(&[])?;
+
+        let items = self.regex.capture_names().enumerate().skip(1).map(|(i, n)| {
+            let name = n.map_or_else(|| Cow::Owned(i.to_string()), Cow::Borrowed);
+            self.value(name, caps.get(i))
+        });
+
+        let mut seq = SeqDeserializer::new(items);
+        let value = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.deserialize_seq(visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool
+        u8 u16 u32 u64
+        i8 i16 i32 i64
+        f32 f64
+        char str string identifier
+        unit bytes byte_buf unit_struct
+        ignored_any option newtype_struct enum
+    }
+}
+
+fn report_slow_match(options: &Options, regex: &str, input_len: usize, start: Instant) {
+    let elapsed = start.elapsed();
+
+    if let Some((threshold, hook)) = &options.slow_match {
+        if elapsed > *threshold {
+            hook(&SlowMatch {
+                id: options.id.as_deref().unwrap_or(regex),
+                input_len,
+                elapsed,
+            });
+        }
+    }
+}
+
+pub(crate) fn check_deadline(deadline: Option<Instant>) -> Result<()> {
+    match deadline {
+        Some(deadline) if Instant::now() >= deadline => Err(Error::Timeout()),
+        _ => Ok(()),
+    }
+}
+
+/// Split an integer with a `0x`, `0o` or `0b` prefix into sign, radix and digits
+fn split_radix_prefix(value: &str) -> Option<(bool, u32, &str)> {
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(value) => (true, value),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+
+    let radix = match value.get(..2)? {
+        "0x" | "0X" => 16,
+        "0o" | "0O" => 8,
+        "0b" | "0B" => 2,
+        _ => return None,
+    };
+
+    Some((negative, radix, &value[2..]))
+}
+
+fn trim(value: &str, enabled: bool) -> &str {
+    if enabled { value.trim() } else { value }
+}
+
+/// Returns the span of `part` which must be a slice of `value` located at `span` in the input
+fn sub_span(span: &Option<Range<usize>>, value: &[u8], part: &[u8]) -> Option<Range<usize>> {
+    span.as_ref().map(|span| {
+        let start = span.start + (part.as_ptr() as usize - value.as_ptr() as usize);
+        start..start + part.len()
+    })
+}
+
+struct Value<'a, 'de> {
+    name: Cow<'a, str>,
+    value: Cow<'de, str>,
+    span: Option<Range<usize>>,
+    options: &'a Options,
+    deadline: Option<Instant>,
+    trace: Option<&'a Trace>,
+}
+
+impl Value<'_, '_> {
+    fn parse<T>(&self) -> Result<T> where T: FromStr + Debug {
+        check_deadline(self.deadline)?;
+        let value: T = self.value.parse().map_err(|_| self.get_parse_error(std::any::type_name::<T>()))?;
+        self.record(std::any::type_name::<T>(), &value);
+        Ok(value)
+    }
+
+    fn parse_int<T>(&self) -> Result<T> where T: FromStr + TryFrom<i128> + Debug {
+        let (negative, radix, digits) = match split_radix_prefix(&self.value) {
+            Some(prefix) if self.options.radix_prefixes => prefix,
+            _ => return self.parse(),
+        };
+
+        check_deadline(self.deadline)?;
+
+        let value = i128::from_str_radix(digits, radix).ok()
+            .filter(|_| !digits.starts_with(['+', '-']))
+            .map(|value| if negative { -value } else { value })
+            .and_then(|value| T::try_from(value).ok())
+            .ok_or_else(|| self.get_parse_error(std::any::type_name::<T>()))?;
+
+        self.record(std::any::type_name::<T>(), &value);
+        Ok(value)
+    }
+
+    fn record<T>(&self, type_name: &'static str, value: &T) where T: Debug + ?Sized {
+        if let Some(trace) = self.trace {
+            trace.record(&self.name, type_name, format!("{:?}", value));
+        }
+    }
+
+    fn get_parse_error(&self, expected: &'static str) -> Error {
         Error::BadValue {
-            name: self.name.clone(),
-            value: self.value.clone(),
+            name: self.name.to_string(),
+            value: self.value.to_string(),
+            span: self.span.clone(),
+            expected,
         }
     }
 }
 
-impl<'de> IntoDeserializer<'de, Error> for Value {
+impl<'de> IntoDeserializer<'de, Error> for Value<'_, 'de> {
     type Deserializer = Self;
 
     fn into_deserializer(self) -> Self::Deserializer {
@@ -80,53 +390,65 @@ impl<'de> IntoDeserializer<'de, Error> for Value {
     }
 }
 
-impl<'de> serde::Deserializer<'de> for Value {
+impl<'de> serde::Deserializer<'de> for Value<'_, 'de> {
     type Error = Error;
 
     fn deserialize_any<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
-        self.value.into_deserializer().deserialize_any(visitor)
+        check_deadline(self.deadline)?;
+        self.record("str", &*self.value);
+
+        match self.value {
+            Cow::Borrowed(value) => visitor.visit_borrowed_str(value),
+            Cow::Owned(value) => visitor.visit_string(value),
+        }
     }
 
     fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
-        if self.value.eq_ignore_ascii_case("true") {
-            visitor.visit_bool(true)
-        } else if self.value.eq_ignore_ascii_case("false") {
-            visitor.visit_bool(false)
+        check_deadline(self.deadline)?;
+        let matches = |token: &String| self.value.eq_ignore_ascii_case(token);
+
+        let value = if self.value.eq_ignore_ascii_case("true") || self.options.true_tokens.iter().any(matches) {
+            true
+        } else if self.value.eq_ignore_ascii_case("false") || self.options.false_tokens.iter().any(matches) {
+            false
         } else {
-            Err(self.get_parse_error())
-        }
+            return Err(self.get_parse_error("bool"));
+        };
+
+        self.record("bool", &value);
+        visitor.visit_bool(value)
     }
 
     fn deserialize_i8<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
-        visitor.visit_i8(self.parse()?)
+        visitor.visit_i8(self.parse_int()?)
     }
 
     fn deserialize_i16<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
-        visitor.visit_i16(self.parse()?)
+        visitor.visit_i16(self.parse_int()?)
     }
 
     fn deserialize_i32<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
-        visitor.visit_i32(self.parse()?)
+        visitor.visit_i32(self.parse_int()?)
     }
 
     fn deserialize_i64<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
-        visitor.visit_i64(self.parse()?)
+        visitor.visit_i64(self.parse_int()?)
     }
 
     fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
-        visitor.visit_u8(self.parse()?)
+        visitor.visit_u8(self.parse_int()?)
     }
 
     fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
-        visitor.visit_u16(self.parse()?)
+        visitor.visit_u16(self.parse_int()?)
     }
 
     fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
-        visitor.visit_u32(self.parse()?)
+        visitor.visit_u32(self.parse_int()?)
     }
 
     fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
-        visitor.visit_u64(self.parse()?)
+        visitor.visit_u64(self.parse_int()?)
     }
 
     fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
@@ -139,24 +461,327 @@ impl<'de> serde::Deserializer<'de> for Value {
 
     fn deserialize_option<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
         if self.value.is_empty() {
+            self.record("option", &None::<()>);
             visitor.visit_none()
         } else {
             visitor.visit_some(self)
         }
     }
 
-    fn deserialize_newtype_struct<V>(self, _: &'static str, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
-        visitor.visit_newtype_struct(self)
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        if name == AMOUNT_TOKEN {
+            check_deadline(self.deadline)?;
+            let amount = Amount::parse(&self.value, &self.options.amount_format).ok_or_else(|| self.get_parse_error("Amount"))?;
+            self.record("Amount", &amount);
+
+            // The visitor of Amount accepts the parsed parts to avoid parsing the value twice
+            visitor.visit_seq(AmountParts::new(amount))
+        } else {
+            visitor.visit_newtype_struct(self)
+        }
     }
 
     fn deserialize_enum<V>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value> where V: Visitor<'de> {
-        visitor.visit_enum(self.value.into_deserializer())
+        check_deadline(self.deadline)?;
+        self.record("enum", &format_args!("{}", self.value));
+
+        match self.value {
+            Cow::Borrowed(value) => visitor.visit_enum(BorrowedStrDeserializer::new(value)),
+            Cow::Owned(value) => visitor.visit_enum(StringDeserializer::new(value)),
+        }
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        let separator = match self.options.separators.get(self.name.as_ref()) {
+            Some(separator) => separator,
+            None => return self.deserialize_any(visitor),
+        };
+
+        check_deadline(self.deadline)?;
+
+        let enabled = self.options.trim;
+        let span = |part: &str| sub_span(&self.span, self.value.as_bytes(), part.as_bytes());
+
+        // Splitting an empty string would result in one empty item.
+        // Spans of converted values can't be mapped to the input and are kept as they are.
+        let values: Vec<(Cow<'de, str>, Option<Range<usize>>)> = match &self.value {
+            value if value.is_empty() => vec![],
+            Cow::Borrowed(value) => separator.split(value).map(|value| {
+                let value = trim(value, enabled);
+                (Cow::Borrowed(value), span(value))
+            }).collect(),
+            Cow::Owned(value) => separator.split(value).map(|value| {
+                (Cow::Owned(trim(value, enabled).to_owned()), self.span.clone())
+            }).collect(),
+        };
+
+        let items = values.into_iter().map(|(value, span)| Value {
+            name: self.name.clone(),
+            value,
+            span,
+            options: self.options,
+            deadline: self.deadline,
+            trace: self.trace,
+        });
+
+        let mut seq = SeqDeserializer::new(items);
+        let value = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.deserialize_seq(visitor)
     }
 
     //Remaining values can either be parsed as string or are not directly supported
     serde::forward_to_deserialize_any! {
         char str string identifier
-        unit seq bytes byte_buf map unit_struct
-        tuple_struct tuple ignored_any struct
+        unit bytes byte_buf map unit_struct
+        tuple_struct ignored_any struct
+    }
+}
+
+/// A captured value of a byte input.
+///
+/// Bytes are passed to serde as they are while all other types are converted from the UTF-8 representation.
+struct BytesValue<'a, 'de> {
+    name: Cow<'a, str>,
+    value: &'de [u8],
+    span: Option<Range<usize>>,
+    options: &'a Options,
+    deadline: Option<Instant>,
+}
+
+impl<'a, 'de> BytesValue<'a, 'de> {
+    /// Convert into a string value, replacing invalid UTF-8 sequences if lossy conversion is enabled
+    fn into_str(self) -> Result<Value<'a, 'de>> {
+        let value = match std::str::from_utf8(self.value) {
+            Ok(value) => Cow::Borrowed(value),
+            Err(_) if self.options.lossy_utf8 => String::from_utf8_lossy(self.value),
+            Err(_) => return Err(Error::BadValue {
+                name: self.name.to_string(),
+                value: String::from_utf8_lossy(self.value).into_owned(),
+                span: self.span,
+                expected: "str",
+            }),
+        };
+
+        // The value was already trimmed by BytesDeserializer::value
+        Ok(Value {
+            name: self.name,
+            value,
+            span: self.span,
+            options: self.options,
+            deadline: self.deadline,
+            trace: None,
+        })
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for BytesValue<'_, 'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+impl<'de> serde::Deserializer<'de> for BytesValue<'_, 'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.into_str()?.deserialize_any(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        check_deadline(self.deadline)?;
+        visitor.visit_borrowed_bytes(self.value)
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        if self.value.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        if name == AMOUNT_TOKEN {
+            self.into_str()?.deserialize_newtype_struct(name, visitor)
+        } else {
+            visitor.visit_newtype_struct(self)
+        }
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        // Vec<u8> is deserialized as sequence, so the bytes are only split if a separator was set
+        if self.options.separators.contains_key(self.name.as_ref()) {
+            return self.into_str()?.deserialize_seq(visitor);
+        }
+
+        check_deadline(self.deadline)?;
+
+        let name = &self.name;
+        let start = self.span.as_ref().map(|span| span.start);
+        let bytes = self.value.iter().enumerate().map(|(i, &byte)| ByteValue {
+            name,
+            byte,
+            span: start.map(|start| start + i..start + i + 1),
+        });
+
+        let mut seq = SeqDeserializer::new(bytes);
+        let value = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.into_str()?.deserialize_bool(visitor)
+    }
+
+    fn deserialize_i8<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.into_str()?.deserialize_i8(visitor)
+    }
+
+    fn deserialize_i16<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.into_str()?.deserialize_i16(visitor)
+    }
+
+    fn deserialize_i32<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.into_str()?.deserialize_i32(visitor)
+    }
+
+    fn deserialize_i64<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.into_str()?.deserialize_i64(visitor)
+    }
+
+    fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.into_str()?.deserialize_u8(visitor)
+    }
+
+    fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.into_str()?.deserialize_u16(visitor)
+    }
+
+    fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.into_str()?.deserialize_u32(visitor)
+    }
+
+    fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.into_str()?.deserialize_u64(visitor)
+    }
+
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.into_str()?.deserialize_f32(visitor)
+    }
+
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.into_str()?.deserialize_f64(visitor)
+    }
+
+    fn deserialize_enum<V>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.into_str()?.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.deserialize_seq(visitor)
+    }
+
+    //Remaining values are converted into strings
+    serde::forward_to_deserialize_any! {
+        char str string identifier
+        unit map unit_struct
+        tuple_struct ignored_any struct
+    }
+}
+
+/// A single byte of a [BytesValue] that is deserialized as sequence.
+///
+/// Only `u8` is accepted to prevent bytes from being silently widened into other numbers.
+struct ByteValue<'a> {
+    name: &'a str,
+    byte: u8,
+    span: Option<Range<usize>>,
+}
+
+impl ByteValue<'_> {
+    fn get_parse_error(&self, expected: &'static str) -> Error {
+        Error::BadValue {
+            name: self.name.to_owned(),
+            value: String::from_utf8_lossy(&[self.byte]).into_owned(),
+            span: self.span.clone(),
+            expected,
+        }
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for ByteValue<'_> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+macro_rules! reject_byte {
+    ($($method:ident($($ty:ty),*) => $expected:literal,)*) => {
+        $(
+            fn $method<V>(self, $(_: $ty,)* _visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+                Err(self.get_parse_error($expected))
+            }
+        )*
+    };
+}
+
+impl<'de> serde::Deserializer<'de> for ByteValue<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        visitor.visit_u8(self.byte)
+    }
+
+    fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        visitor.visit_u8(self.byte)
+    }
+
+    reject_byte! {
+        deserialize_bool() => "bool",
+        deserialize_i8() => "i8",
+        deserialize_i16() => "i16",
+        deserialize_i32() => "i32",
+        deserialize_i64() => "i64",
+        deserialize_i128() => "i128",
+        deserialize_u16() => "u16",
+        deserialize_u32() => "u32",
+        deserialize_u64() => "u64",
+        deserialize_u128() => "u128",
+        deserialize_f32() => "f32",
+        deserialize_f64() => "f64",
+        deserialize_char() => "char",
+        deserialize_str() => "str",
+        deserialize_string() => "String",
+        deserialize_bytes() => "bytes",
+        deserialize_byte_buf() => "bytes",
+        deserialize_option() => "option",
+        deserialize_unit() => "unit",
+        deserialize_unit_struct(&'static str) => "unit struct",
+        deserialize_newtype_struct(&'static str) => "newtype struct",
+        deserialize_seq() => "seq",
+        deserialize_tuple(usize) => "tuple",
+        deserialize_tuple_struct(&'static str, usize) => "tuple struct",
+        deserialize_map() => "map",
+        deserialize_struct(&'static str, &'static [&'static str]) => "struct",
+        deserialize_enum(&'static str, &'static [&'static str]) => "enum",
+        deserialize_identifier() => "identifier",
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        visitor.visit_unit()
     }
 }
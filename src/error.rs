@@ -1,4 +1,5 @@
 use std::fmt::{Display, Formatter};
+use std::ops::Range;
 
 /// An error that occurred during deserialization.
 #[derive(Debug)]
@@ -6,8 +7,37 @@ pub enum Error {
     /// An error occurred while parsing the regular expression
     BadRegex(regex::Error),
 
+    /// The compiled program of the regular expression exceeds the limit set with [Builder::size_limit](crate::Builder::size_limit)
+    PatternTooBig {
+        /// The size limit in bytes
+        limit: usize,
+    },
+
+    /// A [PatternRegistry](crate::PatternRegistry) can't cache any pattern because of its quota
+    TooManyPatterns {
+        /// The maximum number of patterns per tenant
+        limit: usize,
+    },
+
     /// The string doesn't match the pattern
-    NoMatch(),
+    NoMatch {
+        /// The names of all named groups defined by the pattern
+        groups: Vec<String>,
+
+        /// The names of all fields of the target struct without a group of the same name.
+        ///
+        /// Optional fields and fields with a default value are included as well,
+        /// so these fields don't necessarily cause the mismatch.
+        unmatched_fields: Vec<String>,
+    },
+
+    /// A field of the target struct has no value.
+    ///
+    /// Either the pattern doesn't define a group with the name of the field or the group didn't participate in the match.
+    MissingGroup {
+        /// The name of the group
+        name: String,
+    },
 
     /// A value couldn't be parsed into the required type
     BadValue {
@@ -16,16 +46,72 @@ pub enum Error {
 
         /// The value that couldn't be converted to the target value
         value: String,
+
+        /// The byte offsets of the value in the input or `None` if the group didn't participate in the match
+        span: Option<Range<usize>>,
+
+        /// The name of the expected type like `u16`
+        expected: &'static str,
     },
 
+    /// The time budget set with [Builder::timeout](crate::Builder::timeout) was exceeded
+    Timeout(),
+
+    /// The pattern can't be searched with a time budget set with [Builder::timeout](crate::Builder::timeout)
+    TimeoutUnsupported(String),
+
     /// Some other deserialization/serde related error
     Custom(String),
 }
 
+impl Error {
+    /// Returns the byte offsets of the value in the input that caused the error
+    ///
+    /// # Example
+    /// ```rust
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize, Debug)]
+    /// struct Dimension {
+    ///     width: u16,
+    ///     height: u16
+    /// }
+    ///
+    /// let input = "800x70000";
+    /// let err = de_regex::from_str::<Dimension>(input, r"^(?P<width>\d+)x(?P<height>\d+)$").unwrap_err();
+    ///
+    /// let span = err.span().unwrap();
+    /// assert_eq!(&input[span.clone()], "70000");
+    ///
+    /// println!("{}", input);
+    /// println!("{}{} {}", " ".repeat(span.start), "^".repeat(span.len()), err);
+    /// ```
+    pub fn span(&self) -> Option<Range<usize>> {
+        match self {
+            Error::BadValue { span, .. } => span.clone(),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn no_match<'a>(groups: impl Iterator<Item=Option<&'a str>>, fields: &[&str]) -> Error {
+        let groups: Vec<_> = groups.flatten().map(str::to_owned).collect();
+        let unmatched_fields = fields.iter().filter(|field| !groups.iter().any(|group| group == *field)).map(|field| field.to_string()).collect();
+
+        Error::NoMatch {
+            groups,
+            unmatched_fields,
+        }
+    }
+}
+
 impl serde::de::Error for Error {
     fn custom<T>(msg: T) -> Self where T: Display {
         Self::Custom(msg.to_string())
     }
+
+    fn missing_field(field: &'static str) -> Self {
+        Self::MissingGroup { name: field.to_owned() }
+    }
 }
 
 impl std::error::Error for Error {}
@@ -35,8 +121,29 @@ impl Display for Error {
         use Error::*;
         match self {
             BadRegex(err) => err.fmt(f),
-            NoMatch() => write!(f, "String doesn't match pattern"),
-            BadValue { name, value } => write!(f, "Unable to convert value for group {}: {}", name, value),
+            PatternTooBig { limit } => write!(f, "Compiled pattern exceeds size limit of {} bytes", limit),
+            TooManyPatterns { limit } => write!(f, "Quota of {} patterns per tenant exceeded", limit),
+            NoMatch { unmatched_fields, .. } => {
+                write!(f, "String doesn't match pattern")?;
+
+                if !unmatched_fields.is_empty() {
+                    write!(f, " (fields without a matching group: {})", unmatched_fields.join(", "))?;
+                }
+
+                Ok(())
+            }
+            MissingGroup { name } => write!(f, "Missing value for group {}", name),
+            BadValue { name, value, span, expected } => {
+                write!(f, "Unable to convert value for group {} to {}: {}", name, expected, value)?;
+
+                if let Some(span) = span {
+                    write!(f, " at {}..{}", span.start, span.end)?;
+                }
+
+                Ok(())
+            }
+            Timeout() => write!(f, "Time budget for deserialization exceeded"),
+            TimeoutUnsupported(err) => write!(f, "Pattern can't be searched with a time budget: {}", err),
             Custom(err) => write!(f, "{}", err),
         }
     }
@@ -0,0 +1,65 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use serde::Deserialize;
+use regex::Regex;
+
+use crate::builder::Options;
+use crate::de;
+use crate::error::*;
+
+/// An iterator that deserializes all non overlapping matches in an input string.
+///
+/// This is created by [from_str_iter](crate::from_str_iter) or [Pattern::from_str_iter](crate::Pattern::from_str_iter).
+pub struct Iter<'de, T> {
+    input: &'de str,
+    regex: Regex,
+    options: Arc<Options>,
+    pos: usize,
+    last_end: Option<usize>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'de, T> Iter<'de, T> {
+    pub(crate) fn new(input: &'de str, regex: Regex, options: Arc<Options>) -> Iter<'de, T> {
+        Iter {
+            input,
+            regex,
+            options,
+            pos: 0,
+            last_end: None,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T> Iterator for Iter<'de, T> where T: Deserialize<'de> {
+    type Item = std::result::Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos <= self.input.len() {
+            let mut deserializer = de::Deserializer::new(self.input, &self.regex, &self.options);
+            let (start, end) = match deserializer.try_match_at(self.pos) {
+                Ok(span) => span?,
+                Err(err) => {
+                    // Stop after the budget for a search was exceeded
+                    self.pos = self.input.len() + 1;
+                    return Some(Err(err));
+                }
+            };
+
+            // Empty matches directly after the previous match are skipped like in regex::Regex::captures_iter
+            if start == end && Some(end) == self.last_end {
+                self.pos += self.input[self.pos..].chars().next().map_or(1, char::len_utf8);
+                continue;
+            }
+
+            self.pos = end;
+            self.last_end = Some(end);
+
+            return Some(T::deserialize(&mut deserializer));
+        }
+
+        None
+    }
+}
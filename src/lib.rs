@@ -26,24 +26,46 @@ assert_eq!(dim.height, 600);
 # Ok(())
 # }
 ```
+## Unnamed groups
+
+Tuples and tuple structs are deserialized from all capture groups in order of their index:
+
+```rust
+# fn main() -> Result<(), de_regex::Error> {
+let (width, height): (u32, u32) = de_regex::from_str("800x600", r"^(\d+)x(\d+)$")?;
+
+assert_eq!(width, 800);
+assert_eq!(height, 600);
+# Ok(())
+# }
+```
+
+Groups that didn't participate in the match are treated as empty strings.
+
 ## Supported data types
 
 The following data types can be used as struct fields.
 
-- **bool**: Supported values are `true` or `false` case insensitive<br>
-            Example pattern: `^(?P<group_name>(?i)(true|false))$`
+- **bool**: Supported values are `true` or `false` case insensitive. Additional tokens can be set with [Builder::bool_tokens].<br>
+  Example pattern: `^(?P<group_name>(?i)(true|false))$`
 
-- **u8, u16, u32, u64**: Decimal values prefixed with an optional `+`<br>
-            Example pattern: `^(?P<group_name>\+?\d+)$`
+- **u8, u16, u32, u64**: Decimal values prefixed with an optional `+`.
+  Prefixes for other radixes can be enabled with [Builder::radix_prefixes].<br>
+  Example pattern: `^(?P<group_name>\+?\d+)$`
 
-- **i8, i16, i32, i64**: Decimal values prefixed with an optional `+`<br>
-            Example pattern: `^(?P<group_name>[-+]?\d+)$`
+- **i8, i16, i32, i64**: Decimal values prefixed with an optional `+`.
+  Prefixes for other radixes can be enabled with [Builder::radix_prefixes].<br>
+  Example pattern: `^(?P<group_name>[-+]?\d+)$`
 
 - **f32, f64**: See the documentation of the [FromStr](https://doc.rust-lang.org/std/primitive.f32.html#impl-FromStr) implementation of f32/f64 for the valid syntax<br>
-            Example pattern for simple decimal floats: `^(?P<group_name>[-+]?\d+(\.\d*)?)$`
+  Example pattern for simple decimal floats: `^(?P<group_name>[-+]?\d+(\.\d*)?)$`
 
 - **String**: A unicode (utf8) string value.<br>
-            Example pattern: `^(?P<group_name>\w*)$`
+  Example pattern: `^(?P<group_name>\w*)$`
+
+- **`&str`, `Cow<str>`**: A string value borrowed from the input without any allocation.
+  `Cow<str>` fields need the `#[serde(borrow)]` attribute to be borrowed.<br>
+  Example pattern: `^(?P<group_name>\w*)$`
 
 - **Tuple struct**: A tuple struct with one field (New Type Idiom). The struct needs to implement ´Deserialize´:
     ```rust
@@ -64,10 +86,50 @@ The following data types can be used as struct fields.
       };
     ```
 
+- **[Amount]**: A decimal amount of money with an optional currency like `$1,234.56` or `1.234,56 €`.
+  The format can be changed with [Builder::amount_format].<br>
+  Example pattern: `^(?P<group_name>.*)$`
+
+- **`&[u8]`, `Vec<u8>`**: The raw bytes of a group when deserializing a byte input with [from_bytes].
+  Other types are converted from the UTF-8 representation of the bytes.<br>
+  Example pattern: `(?-u)^(?P<group_name>.*)$`
+
+- **Vec<>**: A sequence of all types above if a separator for the group was set with [Builder::split_field]
+
 - **Option<>**: All types above can be used as an optional value
 
 Other data types supported by `serde` might work but are not officially supported and tested.
 
+## Derive macro
+
+With the `derive` feature enabled the pattern can be attached to the struct itself.
+The pattern is validated at compile time and every required field must have a matching named group:
+
+```rust,ignore
+use serde::Deserialize;
+use de_regex::FromRegex;
+
+#[derive(Deserialize, FromRegex)]
+#[regex(pattern = r"^(?P<width>\d+)x(?P<height>\d+)$")]
+struct Dimension {
+    width: u32,
+    height: u32
+}
+
+let dim: Dimension = "800x600".parse()?;
+let dim = Dimension::from_regex_str("800x600")?;
+```
+
+## Reusing patterns
+
+[from_str] compiles the regular expression on every call. If the same expression is applied
+to many inputs compile it once into a [Pattern]. Non default options like a time budget
+for untrusted inputs can be set with a [Builder].
+
+Types that need to implement [FromStr](std::str::FromStr) can be wrapped into a [RegexParsed].
+
+Services compiling user defined patterns can cache them per tenant with a [PatternRegistry].
+
 ### Words of wisdom
 
 If your regular expression looks like a behemoth no mere mortal will ever understand, please reconsider using this crate
@@ -87,16 +149,55 @@ If your regular expression looks like a behemoth no mere mortal will ever unders
  *    Responsible to deserialize struct members.
  *    For most types parsing is based on std::str::FromStr
  *
+ * Byte inputs are handled by struct BytesDeserializer and struct BytesValue.
+ * Except for bytes all values are converted into a Value for parsing.
+ *
  */
 
 mod error;
 mod de;
+mod builder;
+mod pattern;
+mod registry;
+mod parsed;
+mod render;
+mod amount;
+mod iter;
+mod timeout;
 
 pub use error::Error;
+pub use builder::{Builder, SlowMatch};
+pub use pattern::{Pattern, Captures, Group};
+pub use registry::PatternRegistry;
+pub use parsed::{HasPattern, RegexParsed};
+pub use amount::{Amount, AmountFormat, SymbolPosition};
+pub use iter::Iter;
+
+#[cfg(feature = "derive")]
+pub use de_regex_derive::FromRegex;
+
+// Used by the code generated by the derive macro
+#[doc(hidden)]
+pub mod __private {
+    pub use serde::Deserialize;
+    pub use regex::{Error as RegexError, Regex};
+
+    use crate::{de, Error};
+
+    /// Deserialize an input string with a borrowed regex that is shared between calls
+    pub fn from_str_regex<'a, T>(input: &'a str, regex: &Regex) -> std::result::Result<T, Error> where T: Deserialize<'a> {
+        let options = Default::default();
+        let mut deserializer = de::Deserializer::new(input, regex, &options);
+        T::deserialize(&mut deserializer)
+    }
+}
 
 use serde::Deserialize;
+use serde::de::DeserializeOwned;
 use regex::Regex;
 
+use std::sync::Arc;
+
 /// Deserialize an input string into a struct.
 ///
 /// # Example
@@ -121,7 +222,7 @@ use regex::Regex;
 /// # }
 /// ```
 pub fn from_str<'a, T>(input: &'a str, regex: &str) -> std::result::Result<T, Error> where T: Deserialize<'a> {
-    let regex = Regex::new(&regex).map_err(Error::BadRegex)?;
+    let regex = Regex::new(regex).map_err(Error::BadRegex)?;
     from_str_regex(input, regex)
 }
 
@@ -150,10 +251,268 @@ pub fn from_str<'a, T>(input: &'a str, regex: &str) -> std::result::Result<T, Er
 /// # }
 /// ```
 pub fn from_str_regex<'a, T>(input: &'a str, regex: Regex) -> std::result::Result<T, Error> where T: Deserialize<'a> {
-    let mut deserializer = de::Deserializer::new(input, regex);
+    __private::from_str_regex(input, &regex)
+}
+
+/// Deserialize a byte input into a struct.
+///
+/// Captured values are passed to serde as bytes. Fields like `&[u8]` or `Vec<u8>` get the raw bytes
+/// of a group while all other types are converted from its UTF-8 representation.
+/// Values with invalid UTF-8 sequences result in [Error::BadValue]. Use [from_bytes_lossy] to replace them instead.
+///
+/// # Example
+/// ```rust
+/// # fn main() -> Result<(), de_regex::Error> {
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Frame<'a> {
+///     length: u32,
+///     payload: &'a [u8],
+/// }
+///
+/// let pattern = r"(?s-u)^(?P<length>\d+):(?P<payload>.*)$";
+/// let input = b"3:\x00\xff\x10";
+///
+/// let frame: Frame = de_regex::from_bytes(input, pattern)?;
+///
+/// assert_eq!(frame.length, 3);
+/// assert_eq!(frame.payload, b"\x00\xff\x10");
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_bytes<'a, T>(input: &'a [u8], regex: &str) -> std::result::Result<T, Error> where T: Deserialize<'a> {
+    let regex = regex::bytes::Regex::new(regex).map_err(Error::BadRegex)?;
+    from_bytes_regex(input, regex)
+}
+
+/// Deserialize a byte input into a struct using a precompiled [regex::bytes::Regex].
+///
+/// See [from_bytes] for details.
+pub fn from_bytes_regex<'a, T>(input: &'a [u8], regex: regex::bytes::Regex) -> std::result::Result<T, Error> where T: Deserialize<'a> {
+    let options = Default::default();
+    let mut deserializer = de::BytesDeserializer::new(input, &regex, &options);
+    T::deserialize(&mut deserializer)
+}
+
+/// Deserialize a byte input into a struct replacing invalid UTF-8 sequences.
+///
+/// Works like [from_bytes] but values with invalid UTF-8 sequences are converted with
+/// [String::from_utf8_lossy]. Such values can't be borrowed from the input.
+///
+/// # Example
+/// ```rust
+/// # fn main() -> Result<(), de_regex::Error> {
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct File {
+///     name: String,
+///     size: u64,
+/// }
+///
+/// let file: File = de_regex::from_bytes_lossy(b"caf\xe9.txt 42", r"(?-u)^(?P<name>\S+) (?P<size>\d+)$")?;
+///
+/// assert_eq!(file.name, "caf\u{FFFD}.txt");
+/// assert_eq!(file.size, 42);
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_bytes_lossy<'a, T>(input: &'a [u8], regex: &str) -> std::result::Result<T, Error> where T: Deserialize<'a> {
+    let regex = regex::bytes::Regex::new(regex).map_err(Error::BadRegex)?;
+    from_bytes_regex_lossy(input, regex)
+}
+
+/// Deserialize a byte input into a struct using a precompiled [regex::bytes::Regex] and replacing invalid UTF-8 sequences.
+///
+/// See [from_bytes_lossy] for details.
+pub fn from_bytes_regex_lossy<'a, T>(input: &'a [u8], regex: regex::bytes::Regex) -> std::result::Result<T, Error> where T: Deserialize<'a> {
+    let options = builder::Options {
+        lossy_utf8: true,
+        ..Default::default()
+    };
+
+    let mut deserializer = de::BytesDeserializer::new(input, &regex, &options);
     T::deserialize(&mut deserializer)
 }
 
+/// Deserialize all non overlapping matches in an input string into a vector of structs.
+///
+/// An input without any match results in an empty vector.
+/// Use [from_str_iter] to process the matches lazily.
+///
+/// # Example
+/// ```rust
+/// # fn main() -> Result<(), de_regex::Error> {
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Dimension {
+///     width: u32,
+///     height: u32
+/// }
+///
+/// let pattern = r"(?P<width>\d+)x(?P<height>\d+)";
+/// let input = "800x600, 1024x768";
+///
+/// let dims: Vec<Dimension> = de_regex::from_str_all(input, pattern)?;
+///
+/// assert_eq!(dims.len(), 2);
+/// assert_eq!(dims[1].width, 1024);
+/// assert_eq!(dims[1].height, 768);
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_str_all<'a, T>(input: &'a str, regex: &str) -> std::result::Result<Vec<T>, Error> where T: Deserialize<'a> {
+    from_str_iter(input, regex)?.collect()
+}
+
+/// Deserialize all non overlapping matches in an input string into a vector of structs.
+///
+/// See [from_str_all] for details.
+pub fn from_str_regex_all<'a, T>(input: &'a str, regex: Regex) -> std::result::Result<Vec<T>, Error> where T: Deserialize<'a> {
+    from_str_regex_iter(input, regex).collect()
+}
+
+/// Returns an iterator that lazily deserializes all non overlapping matches in an input string.
+///
+/// # Example
+/// ```rust
+/// # fn main() -> Result<(), de_regex::Error> {
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Entry {
+///     key: String,
+///     value: u32,
+/// }
+///
+/// let input = "a=1 b=2 c=x";
+///
+/// for entry in de_regex::from_str_iter::<Entry>(input, r"(?P<key>\w+)=(?P<value>\w+)")? {
+///     match entry {
+///         Ok(entry) => assert!(entry.value < 3),
+///         Err(err) => assert!(matches!(err, de_regex::Error::BadValue { .. })),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_str_iter<'a, T>(input: &'a str, regex: &str) -> std::result::Result<Iter<'a, T>, Error> where T: Deserialize<'a> {
+    let regex = Regex::new(regex).map_err(Error::BadRegex)?;
+    Ok(from_str_regex_iter(input, regex))
+}
+
+/// Returns an iterator that lazily deserializes all non overlapping matches in an input string.
+///
+/// See [from_str_iter] for details.
+pub fn from_str_regex_iter<'a, T>(input: &'a str, regex: Regex) -> Iter<'a, T> where T: Deserialize<'a> {
+    Iter::new(input, regex, Arc::new(Default::default()))
+}
+
+/// Deserialize an input string into a struct or return `None` if the input doesn't match the pattern.
+///
+/// Unlike [from_str] a non matching input is not treated as an error. All other errors like
+/// a bad regular expression or values that can't be converted are still returned as errors.
+///
+/// # Example
+/// ```rust
+/// # fn main() -> Result<(), de_regex::Error> {
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Dimension {
+///     width: u32,
+///     height: u32
+/// }
+///
+/// let pattern = r"^(?P<width>\d+)x(?P<height>\d+)$";
+///
+/// let dim: Option<Dimension> = de_regex::try_from_str("800x600", pattern)?;
+/// assert!(dim.is_some());
+///
+/// let dim: Option<Dimension> = de_regex::try_from_str("800-600", pattern)?;
+/// assert!(dim.is_none());
+/// # Ok(())
+/// # }
+/// ```
+pub fn try_from_str<'a, T>(input: &'a str, regex: &str) -> std::result::Result<Option<T>, Error> where T: Deserialize<'a> {
+    let regex = Regex::new(regex).map_err(Error::BadRegex)?;
+    let options = Default::default();
+    let mut deserializer = de::Deserializer::new(input, &regex, &options);
+
+    if deserializer.try_match()? {
+        T::deserialize(&mut deserializer).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Deserialize an owned input string into a struct.
+///
+/// This avoids lifetime issues if the input is created on the fly and not needed afterwards.
+///
+/// # Example
+/// ```rust
+/// # fn main() -> Result<(), de_regex::Error> {
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Dimension {
+///     width: u32,
+///     height: u32
+/// }
+///
+/// fn read_input() -> String {
+///     "800x600".to_owned()
+/// }
+///
+/// let dim: Dimension = de_regex::from_string(read_input(), r"^(?P<width>\d+)x(?P<height>\d+)$")?;
+///
+/// assert_eq!(dim.width, 800);
+/// assert_eq!(dim.height, 600);
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_string<T>(input: impl Into<String>, regex: &str) -> std::result::Result<T, Error> where T: DeserializeOwned {
+    from_str(&input.into(), regex)
+}
+
+/// Deserialize an input string into a struct and render a table of all groups with their raw and converted values.
+///
+/// The output is stable and meant to be used in snapshot tests of complex patterns.
+/// Unnamed groups are listed by their index and groups split with [Builder::split_field] with all their elements.
+/// Groups that didn't participate in the match or aren't used by the struct are marked with `-`.
+///
+/// # Example
+/// ```rust
+/// # fn main() -> Result<(), de_regex::Error> {
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Dimension {
+///     width: u32,
+///     height: u32
+/// }
+///
+/// let pattern = r"^(?P<width>\d+)x(?P<height>\d+)(?P<unit>px)?$";
+/// let table = de_regex::render::<Dimension>("800x600", pattern)?;
+///
+/// assert_eq!(table, "\
+/// group  | raw   | type | value
+/// -------+-------+------+------
+/// width  | \"800\" | u32  | 800
+/// height | \"600\" | u32  | 600
+/// unit   | -     | -    | -
+/// ");
+/// # Ok(())
+/// # }
+/// ```
+pub fn render<'a, T>(input: &'a str, regex: &str) -> std::result::Result<String, Error> where T: Deserialize<'a> {
+    let regex = Regex::new(regex).map_err(Error::BadRegex)?;
+    render::render::<T>(input, &regex, &Default::default())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -226,7 +585,7 @@ mod test {
         f_str: String,
     }
 
-    const TEST2_PATTERN: &'static str = r"^(?P<f_bool>\w*),(?P<f_u8>\d*),(?P<f_u16>\d*),(?P<f_u32>\d*),(?P<f_u64>\d*),(?P<f_i8>-?\d*),(?P<f_i16>-?\d*),(?P<f_i32>-?\d*),(?P<f_i64>-?\d*),(?P<f_f32>-?\d*\.?\d?),(?P<f_f64>-?\d*\.?\d?),(?P<f_str>\w*)$";
+    const TEST2_PATTERN: &str = r"^(?P<f_bool>\w*),(?P<f_u8>\d*),(?P<f_u16>\d*),(?P<f_u32>\d*),(?P<f_u64>\d*),(?P<f_i8>-?\d*),(?P<f_i16>-?\d*),(?P<f_i32>-?\d*),(?P<f_i64>-?\d*),(?P<f_f32>-?\d*\.?\d?),(?P<f_f64>-?\d*\.?\d?),(?P<f_str>\w*)$";
 
     #[test]
     fn test_supported_types() {
@@ -313,6 +672,29 @@ mod test {
         assert!(from_str::<TestBool>("", regex).is_err());
     }
 
+    #[test]
+    fn test_bool_tokens() {
+        #[derive(Deserialize)]
+        struct TestBool {
+            v: bool,
+        }
+
+        let pattern: Pattern<TestBool> = Builder::new(r"^(?P<v>.*)$")
+            .bool_tokens(&["✓", "Y"], &["✗", "N"])
+            .build()
+            .unwrap();
+
+        assert!(pattern.from_str("✓").unwrap().v);
+        assert!(pattern.from_str("y").unwrap().v);
+        assert!(pattern.from_str("TRUE").unwrap().v);
+        assert!(!pattern.from_str("✗").unwrap().v);
+        assert!(!pattern.from_str("N").unwrap().v);
+        assert!(!pattern.from_str("false").unwrap().v);
+
+        assert!(pattern.from_str("x").is_err());
+        assert!(pattern.from_str("").is_err());
+    }
+
     #[test]
     fn test_uint() {
         #[derive(Deserialize)]
@@ -366,6 +748,43 @@ mod test {
         let output: Result<Test> = from_str(input, regex);
 
         assert!(matches!(output, Err(Error::BadValue{..})), "Expected Error::BadValue got {:?}", output);
+
+        let err = from_str::<Test>("1,-2147483649", regex).unwrap_err();
+        assert!(matches!(&err, Error::BadValue { name, expected, .. } if name == "bar" && *expected == "i32"));
+        assert_eq!(Some(2..13), err.span());
+        assert_eq!("Unable to convert value for group bar to i32: -2147483649 at 2..13", err.to_string());
+    }
+
+    #[test]
+    fn test_error_details() {
+        let err = from_str::<Test>("1;2", r"^(?P<foo>\d+),(?P<baz>\d+)$").unwrap_err();
+        assert!(matches!(&err, Error::NoMatch { groups, unmatched_fields } if groups == &["foo", "baz"] && unmatched_fields == &["bar"]));
+        assert_eq!("String doesn't match pattern (fields without a matching group: bar)", err.to_string());
+        assert_eq!(None, err.span());
+
+        let err = from_str::<Test>("1,2", r"^(?P<foo>\d+),(?P<baz>\d+)$").unwrap_err();
+        assert!(matches!(&err, Error::MissingGroup { name } if name == "bar"));
+
+        let err = from_str::<(u32, u32)>("1;2", r"^(\d+),(\d+)$").unwrap_err();
+        assert!(matches!(&err, Error::NoMatch { groups, unmatched_fields } if groups.is_empty() && unmatched_fields.is_empty()));
+        assert_eq!("String doesn't match pattern", err.to_string());
+
+        let err = from_str::<(u32, u32, String)>("1,x", r"^(\d+),(\d+)?(\w)$").unwrap_err();
+        assert_eq!(None, err.span());
+        assert!(matches!(err, Error::BadValue { name, .. } if name == "2"));
+
+        let pattern: Pattern<(u32, Vec<u8>)> = Builder::new(r"^(\d+):(.*)$")
+            .trim(true)
+            .split_field("2", ",")
+            .build()
+            .unwrap();
+        let input = "1: 2, 300 ,4";
+        let span = pattern.from_str(input).unwrap_err().span().unwrap();
+        assert_eq!("300", &input[span]);
+
+        let input = b"1; 2000 ;3";
+        let err = from_bytes::<(u8, u8, u8)>(input, r"^(\d+);([^;]*);(\d+)$").unwrap_err();
+        assert_eq!(" 2000 ", std::str::from_utf8(&input[err.span().unwrap()]).unwrap());
     }
 
     #[test]
@@ -409,4 +828,659 @@ mod test {
         assert!(from_str::<Test>("foo", regex).is_err());
         assert!(from_str::<Test>("Baz", regex).is_err());
     }
+
+    #[test]
+    fn test_pattern_reuse() {
+        let pattern: Pattern<Test> = Pattern::new(r"^(?P<foo>\d*),(?P<bar>-?\d*)$").unwrap();
+
+        assert_eq!(Test { foo: 1, bar: -2 }, pattern.from_str("1,-2").unwrap());
+        assert_eq!(Test { foo: 3, bar: 4 }, pattern.from_str("3,4").unwrap());
+        assert!(matches!(pattern.from_str("foo"), Err(Error::NoMatch { .. })));
+    }
+
+    #[test]
+    fn test_groups() {
+        let pattern: Pattern<Test> = Pattern::new(r"^(?P<foo>\d*)(,)(?P<bar>-?\d*)$").unwrap();
+
+        let groups: Vec<_> = pattern.groups().map(|g| (g.index(), g.name())).collect();
+        assert_eq!(vec![(0, None), (1, Some("foo")), (2, None), (3, Some("bar"))], groups);
+        assert_eq!(4, pattern.group_count());
+        assert_eq!(Some(3), pattern.group_index("bar"));
+        assert_eq!(None, pattern.group_index("baz"));
+    }
+
+    #[test]
+    fn test_size_limit() {
+        let pattern: Pattern<Test> = Pattern::new(r"^(?P<foo>\d*),(?P<bar>-?\d*)$").unwrap();
+        let size = pattern.compiled_size();
+        assert!(size > 0);
+
+        let pattern: Pattern<Test> = Builder::new(r"^(?P<foo>\d*),(?P<bar>-?\d*)$")
+            .size_limit(size)
+            .build()
+            .unwrap();
+        assert_eq!(size, pattern.compiled_size());
+
+        let output: Result<Pattern<Test>> = Builder::new(r"^(?P<foo>\w{100}),(?P<bar>-?\d*)$")
+            .size_limit(size)
+            .build();
+        assert!(matches!(output, Err(Error::PatternTooBig { limit }) if limit == size));
+
+        // Exceeds the default limit of the regex crate too
+        let output: Result<Pattern<Test>> = Builder::new(r"^(?P<foo>\w{1000}){100}$")
+            .size_limit(1000)
+            .build();
+        assert!(matches!(output, Err(Error::PatternTooBig { limit: 1000 })));
+
+        let output: Result<Pattern<Test>> = Builder::new(r"^(?P<foo>\w{1000}){100}$").build();
+        assert!(matches!(output, Err(Error::BadRegex(_))));
+    }
+
+    #[test]
+    fn test_rest() {
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Test {
+            foo: u32,
+            rest: String,
+        }
+
+        let pattern: Pattern<Test> = Builder::new(r"^(?P<foo>\d+)(?i)")
+            .rest("rest")
+            .build()
+            .unwrap();
+
+        assert_eq!(Test { foo: 1, rest: ",-2 foo".to_owned() }, pattern.from_str("1,-2 foo").unwrap());
+        assert_eq!(Test { foo: 1, rest: " foo".to_owned() }, pattern.from_str("1 foo\nbar").unwrap());
+        assert_eq!(Test { foo: 1, rest: "".to_owned() }, pattern.from_str("1").unwrap());
+
+        let output: Result<Pattern<Test>> = Builder::new(r"^(?P<foo>\d+)").rest("1 invalid").build();
+        assert!(matches!(output, Err(Error::BadRegex(_))));
+    }
+
+    #[test]
+    fn test_slow_match() {
+        use std::sync::{Arc, Mutex};
+        use std::time::Duration;
+
+        let matches = Arc::new(Mutex::new(vec![]));
+        let hook_matches = matches.clone();
+
+        let pattern: Pattern<Test> = Builder::new(r"^(?P<foo>\d*),(?P<bar>-?\d*)$")
+            .id("test")
+            .slow_match(Duration::from_secs(0), move |m| {
+                hook_matches.lock().unwrap().push((m.id().to_owned(), m.input_len()));
+            })
+            .build()
+            .unwrap();
+
+        pattern.from_str("1,-2").unwrap();
+        assert_eq!(vec![("test".to_owned(), 4)], *matches.lock().unwrap());
+
+        let pattern: Pattern<Test> = Builder::new(r"^(?P<foo>\d*),(?P<bar>-?\d*)$")
+            .slow_match(Duration::from_secs(60), |_| panic!("Unexpected slow match"))
+            .build()
+            .unwrap();
+
+        pattern.from_str("1,-2").unwrap();
+    }
+
+    #[test]
+    fn test_timeout() {
+        use std::collections::HashMap;
+        use std::time::{Duration, Instant};
+
+        let pattern: Pattern<Test> = Builder::new(r"^(?P<foo>\d*),(?P<bar>-?\d*)$")
+            .timeout(Duration::from_secs(60))
+            .build()
+            .unwrap();
+
+        assert_eq!(Test { foo: 1, bar: -2 }, pattern.from_str("1,-2").unwrap());
+
+        // Searching the whole input takes considerably longer than the budget
+        let input = "a".repeat(1 << 26);
+        let pattern: Pattern<HashMap<String, String>> = Builder::new(r"(?P<foo>\w{20}b)")
+            .timeout(Duration::from_millis(10))
+            .build()
+            .unwrap();
+
+        let now = Instant::now();
+        let output = pattern.from_str(&input);
+        assert!(matches!(output, Err(Error::Timeout())), "Expected Error::Timeout got {:?}", output);
+        assert!(now.elapsed() < Duration::from_millis(100), "Search wasn't interrupted");
+
+        assert!(matches!(pattern.from_str_iter(&input).collect::<Vec<_>>().as_slice(), [Err(Error::Timeout())]));
+
+        // Extracting the groups of a long match takes considerably longer than the budget
+        let input = "ab ".repeat(1 << 20);
+        let regex = timeout::TimedRegex::new(r"^(?P<x>(?:(?P<w>\w+)\s*)+)$", 10 * (1 << 20)).unwrap();
+
+        let now = Instant::now();
+        let output = regex.captures_in(&input, 0..input.len(), now + Duration::from_millis(10));
+        assert!(matches!(output, Err(Error::Timeout())), "Expected Error::Timeout got {:?}", output);
+        assert!(now.elapsed() < Duration::from_millis(100), "Extraction wasn't interrupted");
+
+        let output = regex.captures_in("ab ab", 0..5, now + Duration::from_secs(60)).unwrap();
+        assert_eq!(Some(vec![Some((0, 5)), Some((0, 5)), Some((3, 5))]), output);
+
+        let pattern = Builder::new(r"\bfoo\b").timeout(Duration::from_secs(1)).build::<Test>();
+        assert!(matches!(pattern, Err(Error::TimeoutUnsupported(_))));
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut registry: PatternRegistry<Test> = PatternRegistry::new();
+        registry.max_patterns(2);
+
+        let p1 = r"^(?P<foo>\d*),(?P<bar>-?\d*)$";
+        let p2 = r"^(?P<foo>\d*);(?P<bar>-?\d*)$";
+        let p3 = r"^(?P<foo>\d*):(?P<bar>-?\d*)$";
+
+        assert_eq!(Test { foo: 1, bar: -2 }, registry.get("a", p1).unwrap().from_str("1,-2").unwrap());
+        assert_eq!(Test { foo: 1, bar: -2 }, registry.get("a", p2).unwrap().from_str("1;-2").unwrap());
+        assert_eq!(Test { foo: 1, bar: -2 }, registry.get("b", p3).unwrap().from_str("1:-2").unwrap());
+        assert_eq!(2, registry.len("a"));
+        assert_eq!(1, registry.len("b"));
+
+        // p2 is the least recently used pattern of tenant a after this
+        registry.get("a", p1).unwrap();
+        registry.get("a", p3).unwrap();
+        assert_eq!(2, registry.len("a"));
+
+        let size = registry.size("a");
+        registry.remove("a", p2);
+        assert_eq!(size, registry.size("a"));
+        registry.remove("a", p1);
+        assert_eq!(1, registry.len("a"));
+        assert!(registry.size("a") < size);
+
+        registry.remove_tenant("b");
+        assert!(registry.is_empty("b"));
+        assert_eq!(0, registry.size("b"));
+    }
+
+    #[test]
+    fn test_registry_max_size() {
+        let p1 = r"^(?P<foo>\d*),(?P<bar>-?\d*)$";
+        let p2 = r"^(?P<foo>\d*);(?P<bar>-?\d*)$";
+        let size = Pattern::<Test>::new(p1).unwrap().compiled_size();
+
+        let mut registry: PatternRegistry<Test> = PatternRegistry::new();
+        registry.max_size(size);
+
+        registry.get("a", p1).unwrap();
+        registry.get("a", p2).unwrap();
+        assert_eq!(1, registry.len("a"));
+        assert!(registry.size("a") <= size);
+
+        let output = registry.get("a", r"^(?P<foo>\w{100}),(?P<bar>-?\d*)$");
+        assert!(matches!(output, Err(Error::PatternTooBig { .. })));
+        assert_eq!(1, registry.len("a"));
+    }
+
+    #[test]
+    fn test_registry_template() {
+        use std::time::Duration;
+
+        let mut registry: PatternRegistry<Test> = PatternRegistry::new();
+        registry.template(Builder::new("").timeout(Duration::from_secs(60)).trim(true));
+
+        let pattern = registry.get("a", r"^(?P<foo>[^,]*),(?P<bar>[^,]*)$").unwrap();
+        drop(registry);
+        assert_eq!(Test { foo: 1, bar: -2 }, pattern.from_str(" 1 , -2 ").unwrap());
+
+        let mut registry: PatternRegistry<Test> = PatternRegistry::new();
+        registry.template(Builder::new("").timeout(Duration::from_secs(60)));
+        assert!(matches!(registry.get("a", r"\bfoo\b"), Err(Error::TimeoutUnsupported(_))));
+        assert!(registry.is_empty("a"));
+    }
+
+    #[test]
+    fn test_registry_no_patterns() {
+        let mut registry: PatternRegistry<Test> = PatternRegistry::new();
+        registry.max_patterns(0);
+
+        let output = registry.get("a", r"^(?P<foo>\d*),(?P<bar>-?\d*)$");
+        assert!(matches!(output, Err(Error::TooManyPatterns { limit: 0 })));
+        assert_eq!(0, registry.len("a"));
+    }
+
+    #[test]
+    fn test_regex_parsed() {
+        struct TestPattern;
+
+        impl HasPattern for TestPattern {
+            const PATTERN: &'static str = r"^(?P<foo>\d*),(?P<bar>-?\d*)$";
+        }
+
+        let output: RegexParsed<Test, TestPattern> = "1,-2".parse().unwrap();
+        assert_eq!(Test { foo: 1, bar: -2 }, output.into_inner());
+
+        assert!("1:-2".parse::<RegexParsed<Test, TestPattern>>().is_err());
+
+        // The second call uses the cached regex
+        let output: RegexParsed<Test, TestPattern> = "3,4".parse().unwrap();
+        assert_eq!(Test { foo: 3, bar: 4 }, output.into_inner());
+
+        struct BadPattern;
+
+        impl HasPattern for BadPattern {
+            const PATTERN: &'static str = r"^(?P<foo\d*)$";
+        }
+
+        for _ in 0..2 {
+            assert!(matches!("1".parse::<RegexParsed<Test, BadPattern>>(), Err(Error::BadRegex(_))));
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        #[allow(dead_code)]
+        #[derive(Deserialize)]
+        struct Test {
+            f_bool: bool,
+            f_str: String,
+            f_opt: Option<u8>,
+            f_float: f64,
+        }
+
+        let pattern: Pattern<Test> = Pattern::new(r"^(?P<f_bool>\w+),(?P<f_str>\w*),(?P<f_opt>\d*),(?P<f_float>[\d.]+)(?P<unused>!)?$").unwrap();
+
+        assert_eq!(pattern.render("TRUE,foo,,1.5").unwrap(), "\
+group   | raw    | type   | value
+--------+--------+--------+------
+f_bool  | \"TRUE\" | bool   | true
+f_str   | \"foo\"  | str    | \"foo\"
+f_opt   | \"\"     | option | None
+f_float | \"1.5\"  | f64    | 1.5
+unused  | -      | -      | -
+");
+
+        assert!(pattern.render("TRUE,foo,,x").is_err());
+
+        #[allow(dead_code)]
+        #[derive(Deserialize)]
+        struct Split {
+            name: String,
+            values: Vec<u32>,
+        }
+
+        let pattern: Pattern<Split> = Builder::new(r"^(?P<name>\w+)=(?P<values>.*)$")
+            .split_field("values", ",")
+            .build()
+            .unwrap();
+
+        assert_eq!(pattern.render("foo=1,2,3").unwrap(), "\
+group  | raw     | type  | value
+-------+---------+-------+----------
+name   | \"foo\"   | str   | \"foo\"
+values | \"1,2,3\" | [u32] | [1, 2, 3]
+");
+
+        let pattern: Pattern<(u32, u32)> = Pattern::new(r"^(\d+)x(\d+)$").unwrap();
+
+        assert_eq!(pattern.render("800x600").unwrap(), "\
+group | raw   | type | value
+------+-------+------+------
+1     | \"800\" | u32  | 800
+2     | \"600\" | u32  | 600
+");
+    }
+
+    #[test]
+    fn test_from_string() {
+        let regex = r"^(?P<foo>\d*),(?P<bar>-?\d*)$";
+        let output: Test = from_string(format!("{},{}", 1, -2), regex).unwrap();
+
+        assert_eq!(output, Test { foo: 1, bar: -2 });
+
+        let pattern: Pattern<Test> = Pattern::new(regex).unwrap();
+        let output = pattern.from_string(String::from("3,4")).unwrap();
+
+        assert_eq!(output, Test { foo: 3, bar: 4 });
+    }
+
+    #[test]
+    fn test_try_from_str() {
+        let regex = r"^(?P<foo>\w*),(?P<bar>-?\d*)$";
+
+        assert_eq!(Some(Test { foo: 1, bar: -2 }), try_from_str("1,-2", regex).unwrap());
+        assert_eq!(None, try_from_str::<Test>("1;-2", regex).unwrap());
+        assert!(matches!(try_from_str::<Test>("a,-2", regex), Err(Error::BadValue { .. })));
+        assert!(matches!(try_from_str::<Test>("1,-2", r"^(?P<foo\d*)$"), Err(Error::BadRegex(_))));
+
+        let pattern: Pattern<Test> = Pattern::new(regex).unwrap();
+        assert_eq!(Some(Test { foo: 1, bar: -2 }), pattern.try_from_str("1,-2").unwrap());
+        assert_eq!(None, pattern.try_from_str("1;-2").unwrap());
+    }
+
+    #[test]
+    fn test_captures() {
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Foo {
+            foo: u32,
+        }
+
+        let pattern: Pattern<Test> = Pattern::new(r"^(?P<foo>\d*),(?P<bar>-?\d*)$").unwrap();
+        let captures = pattern.captures("1,-2").unwrap();
+
+        assert_eq!(Test { foo: 1, bar: -2 }, captures.deserialize().unwrap());
+        assert_eq!(Foo { foo: 1 }, captures.deserialize().unwrap());
+        assert!(captures.deserialize::<Test3>().is_ok());
+
+        assert!(matches!(pattern.captures("1;-2"), Err(Error::NoMatch { .. })));
+    }
+
+    #[test]
+    fn test_amount() {
+        #[derive(Deserialize)]
+        struct Test {
+            v: Amount,
+        }
+
+        let regex = r"^(?P<v>.*)$";
+        let parse = |input| from_str::<Test>(input, regex).map(|t| t.v);
+
+        assert_eq!(Amount::new(123456, 2, Some("USD")), parse("$1,234.56").unwrap());
+        assert_eq!(Amount::new(-123456, 2, Some("USD")), parse("-$1,234.56").unwrap());
+        assert_eq!(Amount::new(-5, 0, Some("GBP")), parse("£-5").unwrap());
+        assert_eq!(Amount::new(1234, 0, Some("EUR")), parse("1234 €").unwrap());
+        assert_eq!(Amount::new(5, 1, Some("CHF")), parse("CHF 0.5").unwrap());
+        assert_eq!(Amount::new(12, 0, None), parse("12").unwrap());
+        assert_eq!("-0.05 USD", parse("-$0.05").unwrap().to_string());
+        assert_eq!(1234.5, parse("1,234.5").unwrap().to_f64());
+
+        assert!(matches!(parse("$"), Err(Error::BadValue { .. })));
+        assert!(matches!(parse("$1 €"), Err(Error::BadValue { .. })));
+        assert!(matches!(parse("1.2.3"), Err(Error::BadValue { .. })));
+        assert!(matches!(parse("#12"), Err(Error::BadValue { .. })));
+        assert!(matches!(parse("--12"), Err(Error::BadValue { .. })));
+        assert!(matches!(parse("1,5 €"), Err(Error::BadValue { .. })));
+        assert!(matches!(parse("1,,2"), Err(Error::BadValue { .. })));
+        assert!(matches!(parse("1234,567"), Err(Error::BadValue { .. })));
+        assert!(matches!(parse("1,234,56.7"), Err(Error::BadValue { .. })));
+        assert_eq!(Amount::new(1234567, 0, None), parse("1,234,567").unwrap());
+
+        let min = Amount::new(i64::MIN, 0, None);
+        assert_eq!(min, parse(&min.to_string()).unwrap());
+
+        let pattern: Pattern<Test> = Builder::new(regex)
+            .amount_format(AmountFormat {
+                decimal_separator: ',',
+                group_separator: Some('.'),
+                symbol_position: SymbolPosition::Suffix,
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(Amount::new(123456, 2, Some("EUR")), pattern.from_str("1.234,56 €").unwrap().v);
+        assert_eq!(Amount::new(123456, 2, Some("EUR")), pattern.from_str("1.234,56EUR").unwrap().v);
+        assert_eq!(Amount::new(i64::MIN, 2, Some("EUR")), pattern.from_str("-92.233.720.368.547.758,08 €").unwrap().v);
+        assert!(pattern.from_str("€1.234,56").is_err());
+
+        assert_eq!(Amount::new(123456, 2, Some("USD")), "$1,234.56".parse().unwrap());
+    }
+
+    #[test]
+    fn test_from_str_all() {
+        let regex = r"(?P<foo>\d+),(?P<bar>-?\d+)";
+
+        let output: Vec<Test> = from_str_all("1,-2 3,4\n5,6", regex).unwrap();
+        assert_eq!(vec![Test { foo: 1, bar: -2 }, Test { foo: 3, bar: 4 }, Test { foo: 5, bar: 6 }], output);
+
+        let output: Vec<Test> = from_str_all("foo", regex).unwrap();
+        assert!(output.is_empty());
+
+        assert!(matches!(from_str_all::<Test>("1,-2 a,4", r"(?P<foo>\w+),(?P<bar>-?\d+)"), Err(Error::BadValue { .. })));
+        assert!(matches!(from_str_all::<Test>("1,-2", r"(?P<foo\d+)"), Err(Error::BadRegex(_))));
+
+        let output: Vec<Test> = from_str_regex_all("1,-2 3,4", Regex::new(regex).unwrap()).unwrap();
+        assert_eq!(2, output.len());
+
+        let pattern: Pattern<Test> = Pattern::new(regex).unwrap();
+        assert_eq!(2, pattern.from_str_all("1,-2 3,4").unwrap().len());
+    }
+
+    #[test]
+    fn test_from_str_iter() {
+        let regex = r"(?P<foo>\w+),(?P<bar>-?\d+)";
+
+        let mut iter = from_str_iter::<Test>("1,-2 a,4 3,4", regex).unwrap();
+        assert_eq!(Test { foo: 1, bar: -2 }, iter.next().unwrap().unwrap());
+        assert!(iter.next().unwrap().is_err());
+        assert_eq!(Test { foo: 3, bar: 4 }, iter.next().unwrap().unwrap());
+        assert!(iter.next().is_none());
+
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Digits {
+            v: Option<u32>,
+        }
+
+        // Empty matches must behave like regex::Regex::captures_iter
+        let regex = r"(?P<v>\d*)";
+        let input = "12ä3";
+        let expected = Regex::new(regex).unwrap().captures_iter(input).count();
+        let output: Vec<Digits> = from_str_all(input, regex).unwrap();
+        assert_eq!(expected, output.len());
+        assert_eq!(vec![Digits { v: Some(12) }, Digits { v: Some(3) }], output);
+    }
+
+    #[test]
+    fn test_borrowed() {
+        use std::borrow::Cow;
+
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Test<'a> {
+            foo: &'a str,
+            #[serde(borrow)]
+            bar: Cow<'a, str>,
+            baz: Option<&'a str>,
+        }
+
+        let regex = r"^(?P<foo>\w*),(?P<bar>\w*),(?P<baz>\w*)$";
+        let input = String::from("abc,def,");
+        let output: Test = from_str(&input, regex).unwrap();
+
+        assert_eq!(Test { foo: "abc", bar: Cow::Borrowed("def"), baz: None }, output);
+        assert!(matches!(output.bar, Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_tuple() {
+        let regex = r"^(\d+)x(\d+)(?:@(\d+))?$";
+
+        let output: (u32, u32, Option<u32>) = from_str("800x600", regex).unwrap();
+        assert_eq!((800, 600, None), output);
+
+        let output: (u32, u32, Option<u32>) = from_str("800x600@96", regex).unwrap();
+        assert_eq!((800, 600, Some(96)), output);
+
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Dimension(u32, u32, Option<u32>);
+
+        let output: Dimension = from_str("800x600@96", regex).unwrap();
+        assert_eq!(Dimension(800, 600, Some(96)), output);
+
+        let output: Vec<String> = from_str("800x600", regex).unwrap();
+        assert_eq!(vec!["800", "600", ""], output);
+
+        assert!(from_str::<(u32, u32)>("800x600", regex).is_err());
+        assert!(from_str::<(u32, u32, u32, u32)>("800x600", regex).is_err());
+        assert!(matches!(from_str::<(u32, u32, u32)>("800x600", regex), Err(Error::BadValue { name, .. }) if name == "3"));
+    }
+
+    #[test]
+    fn test_tuple_named() {
+        let regex = r"^(?P<width>\d+)x(\d+)$";
+
+        let output: (u32, u32) = from_str("800x600", regex).unwrap();
+        assert_eq!((800, 600), output);
+
+        assert!(matches!(from_str::<(u8, u32)>("800x600", regex), Err(Error::BadValue { name, .. }) if name == "width"));
+    }
+
+    #[test]
+    fn test_split_field() {
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Test {
+            name: String,
+            values: Vec<u32>,
+            flags: Option<Vec<bool>>,
+            pair: (i32, i32),
+        }
+
+        let pattern: Pattern<Test> = Builder::new(r"^(?P<name>\w+): (?P<values>[\w, ]*);(?P<flags>[\w|]*);(?P<pair>.*)$")
+            .split_field("values", r",\s*")
+            .split_field("flags", r"\|")
+            .split_field("pair", r"x")
+            .build()
+            .unwrap();
+
+        assert_eq!(Test {
+            name: "foo".to_owned(),
+            values: vec![1, 2, 3],
+            flags: Some(vec![true, false]),
+            pair: (-1, 2),
+        }, pattern.from_str("foo: 1, 2,3;true|false;-1x2").unwrap());
+
+        assert_eq!(Test {
+            name: "foo".to_owned(),
+            values: vec![],
+            flags: None,
+            pair: (1, 2),
+        }, pattern.from_str("foo: ;;1x2").unwrap());
+
+        assert!(matches!(pattern.from_str("foo: 1,a;;1x2"), Err(Error::BadValue { name, value, .. }) if name == "values" && value == "a"));
+        assert!(pattern.from_str("foo: 1;;1x2x3").is_err());
+
+        let output: Result<Pattern<Test>> = Builder::new(r"^(?P<values>.*)$").split_field("values", r"(").build();
+        assert!(matches!(output, Err(Error::BadRegex(_))));
+
+        let output: Result<Test> = from_str("foo: 1,2;;1x2", r"^(?P<name>\w+): (?P<values>[\d, ]*);(?P<flags>[\w|]*);(?P<pair>.*)$");
+        assert!(output.is_err());
+    }
+
+    #[test]
+    fn test_radix_prefixes() {
+        #[derive(Deserialize)]
+        struct Test {
+            u: u16,
+            i: i8,
+        }
+
+        let pattern: Pattern<Test> = Builder::new(r"^(?P<u>\w+),(?P<i>[-+]?\w+)$")
+            .radix_prefixes(true)
+            .build()
+            .unwrap();
+
+        let parse = |input| pattern.from_str(input).map(|t| (t.u, t.i));
+
+        assert_eq!((0xffff, -0x80), parse("0xFFFF,-0x80").unwrap());
+        assert_eq!((0o17, 0b101), parse("0o17,+0b101").unwrap());
+        assert_eq!((123, -12), parse("123,-12").unwrap());
+
+        assert!(matches!(parse("0x10000,0"), Err(Error::BadValue { name, .. }) if name == "u"));
+        assert!(matches!(parse("0,0x80"), Err(Error::BadValue { name, .. }) if name == "i"));
+        assert!(parse("0b2,0").is_err());
+        assert!(parse("0x,0").is_err());
+        assert!(parse("0,0x-1").is_err());
+
+        assert!(from_str::<Test>("0x10,0", r"^(?P<u>\w+),(?P<i>[-+]?\w+)$").is_err());
+    }
+
+    #[test]
+    fn test_trim() {
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Test<'a> {
+            foo: u32,
+            bar: &'a str,
+            baz: Option<bool>,
+            values: Vec<u8>,
+        }
+
+        let pattern: Pattern<Test> = Builder::new(r"^(?P<foo>[^,]*),(?P<bar>[^,]*),(?P<baz>[^,]*),(?P<values>.*)$")
+            .trim(true)
+            .split_field("values", r"\|")
+            .build()
+            .unwrap();
+
+        assert_eq!(Test { foo: 1, bar: "a b", baz: None, values: vec![1, 2] }, pattern.from_str(" 1 , a b ,  , 1 | 2 ").unwrap());
+    }
+
+    #[test]
+    fn test_empty_as_default() {
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Test {
+            #[serde(default)]
+            foo: u32,
+            bar: Option<i32>,
+            baz: u32,
+        }
+
+        let pattern: Pattern<Test> = Builder::new(r"^(?P<foo>\d*),(?P<bar>-?\d*),(?P<baz>\d*)$")
+            .empty_as_default(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(Test { foo: 0, bar: None, baz: 3 }, pattern.from_str(",,3").unwrap());
+        assert_eq!(Test { foo: 1, bar: Some(-2), baz: 3 }, pattern.from_str("1,-2,3").unwrap());
+        assert!(matches!(pattern.from_str("1,-2,"), Err(Error::MissingGroup { name }) if name == "baz"));
+    }
+
+    #[test]
+    fn test_from_bytes() {
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Test<'a> {
+            id: u16,
+            name: &'a str,
+            raw: &'a [u8],
+            data: Vec<u8>,
+            flag: Option<bool>,
+        }
+
+        let pattern = r"(?-u)^(?P<id>\d+);(?P<name>[^;]*);(?P<raw>[^;]*);(?P<data>[^;]*);(?P<flag>\w*)$";
+
+        let test: Test = from_bytes(b"42;foo;\xff\x00;ab;true", pattern).unwrap();
+        assert_eq!(Test { id: 42, name: "foo", raw: b"\xff\x00", data: vec![b'a', b'b'], flag: Some(true) }, test);
+
+        let test: Test = from_bytes(b"1;;;;", pattern).unwrap();
+        assert_eq!(Test { id: 1, name: "", raw: b"", data: vec![], flag: None }, test);
+
+        let result: Result<Test> = from_bytes(b"1;f\xffo;;;", pattern);
+        assert!(matches!(result, Err(Error::BadValue { name, .. }) if name == "name"));
+
+        let result: Result<Test> = from_bytes(b"x;foo;;;", pattern);
+        assert!(matches!(result, Err(Error::NoMatch { .. })));
+
+        let result: Result<Test> = from_bytes(b"1;foo;;;", r"(");
+        assert!(matches!(result, Err(Error::BadRegex(_))));
+
+        #[derive(Deserialize, Debug)]
+        struct Values {
+            #[allow(dead_code)]
+            values: Vec<u32>,
+        }
+
+        let result: Result<Values> = from_bytes(b"123", r"^(?P<values>\d+)$");
+        assert!(matches!(result, Err(Error::BadValue { name, span, expected, .. }) if name == "values" && span == Some(0..1) && expected == "u32"));
+    }
+
+    #[test]
+    fn test_from_bytes_lossy() {
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Test {
+            name: String,
+            value: u32,
+        }
+
+        let pattern = r"(?-u)^(?P<name>[^;]*);(?P<value>[^;]*)$";
+
+        let test: Test = from_bytes_lossy(b"f\xffo;12", pattern).unwrap();
+        assert_eq!(Test { name: "f\u{FFFD}o".to_owned(), value: 12 }, test);
+
+        let result: Result<(String, u32)> = from_bytes_lossy(b"foo;1\xff", pattern);
+        assert!(matches!(result, Err(Error::BadValue { name, value, span, .. }) if name == "value" && value == "1\u{FFFD}" && span == Some(4..6)));
+
+        let result: Result<Test> = from_bytes(b"f\xffo;12", pattern);
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::str::FromStr;
+use std::sync::{OnceLock, PoisonError, RwLock};
+
+use serde::de::DeserializeOwned;
+use regex::Regex;
+
+use crate::error::Error;
+
+/// Provides the regular expression used by [RegexParsed]
+pub trait HasPattern {
+    /// The regular expression
+    const PATTERN: &'static str;
+}
+
+/// A wrapper implementing [FromStr] for every deserializable type.
+///
+/// The regular expression is provided by the type parameter `P`. It is compiled on first use and cached for the whole process.
+///
+/// # Example
+/// ```rust
+/// # fn main() -> Result<(), de_regex::Error> {
+/// use serde::Deserialize;
+/// use de_regex::{HasPattern, RegexParsed};
+///
+/// #[derive(Deserialize)]
+/// struct Dimension {
+///     width: u32,
+///     height: u32
+/// }
+///
+/// struct DimPattern;
+///
+/// impl HasPattern for DimPattern {
+///     const PATTERN: &'static str = r"^(?P<width>\d+)x(?P<height>\d+)$";
+/// }
+///
+/// let dim = "800x600".parse::<RegexParsed<Dimension, DimPattern>>()?;
+///
+/// assert_eq!(dim.width, 800);
+/// assert_eq!(dim.height, 600);
+/// # Ok(())
+/// # }
+/// ```
+pub struct RegexParsed<T, P> {
+    value: T,
+    marker: PhantomData<fn() -> P>,
+}
+
+impl<T, P> RegexParsed<T, P> {
+    /// Wrap an existing value
+    pub fn new(value: T) -> RegexParsed<T, P> {
+        RegexParsed {
+            value,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the wrapped value
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, P> FromStr for RegexParsed<T, P> where T: DeserializeOwned, P: HasPattern {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crate::__private::from_str_regex(s, cached_regex(P::PATTERN)?).map(RegexParsed::new)
+    }
+}
+
+/// Compiled regular expressions of all patterns parsed so far
+static REGEXES: OnceLock<RwLock<HashMap<&'static str, &'static Regex>>> = OnceLock::new();
+
+fn cached_regex(pattern: &'static str) -> Result<&'static Regex, Error> {
+    let regexes = REGEXES.get_or_init(Default::default);
+
+    if let Some(regex) = regexes.read().unwrap_or_else(PoisonError::into_inner).get(pattern) {
+        return Ok(regex);
+    }
+
+    let regex = Regex::new(pattern).map_err(Error::BadRegex)?;
+
+    // Leaking is bounded by the number of constant patterns of the program
+    let mut regexes = regexes.write().unwrap_or_else(PoisonError::into_inner);
+    Ok(regexes.entry(pattern).or_insert_with(|| Box::leak(Box::new(regex))))
+}
+
+impl<T, P> Deref for RegexParsed<T, P> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T, P> DerefMut for RegexParsed<T, P> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T, P> Clone for RegexParsed<T, P> where T: Clone {
+    fn clone(&self) -> Self {
+        RegexParsed::new(self.value.clone())
+    }
+}
+
+impl<T, P> PartialEq for RegexParsed<T, P> where T: PartialEq {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T, P> Debug for RegexParsed<T, P> where T: Debug {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.value.fmt(f)
+    }
+}
@@ -0,0 +1,228 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use regex::Regex;
+
+use crate::builder::{Builder, Options};
+use crate::de;
+use crate::error::*;
+use crate::render;
+use crate::iter::Iter;
+
+/// A compiled regular expression that deserializes into values of type `T`.
+///
+/// Use a pattern instead of [from_str](crate::from_str) if the same regular expression
+/// is applied to many inputs or if non default options are needed (see [Builder]).
+///
+/// # Example
+/// ```rust
+/// # fn main() -> Result<(), de_regex::Error> {
+/// use serde::Deserialize;
+/// use de_regex::Pattern;
+///
+/// #[derive(Deserialize)]
+/// struct Dimension {
+///     width: u32,
+///     height: u32
+/// }
+///
+/// let pattern: Pattern<Dimension> = Pattern::new(r"^(?P<width>\d+)x(?P<height>\d+)$")?;
+///
+/// for input in &["800x600", "1024x768"] {
+///     let dim = pattern.from_str(input)?;
+///     assert!(dim.width > dim.height);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Pattern<T> {
+    regex: Regex,
+    size: usize,
+    options: Arc<Options>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Pattern<T> {
+    /// Compile a regular expression with default options
+    pub fn new(pattern: &str) -> std::result::Result<Pattern<T>, Error> {
+        Builder::new(pattern).build()
+    }
+
+    pub(crate) fn with_options(regex: Regex, size: usize, options: Options) -> Pattern<T> {
+        Pattern {
+            regex,
+            size,
+            options: Arc::new(options),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the source of the regular expression
+    pub fn as_str(&self) -> &str {
+        self.regex.as_str()
+    }
+
+    /// Returns the approximate heap size in bytes of the compiled program.
+    ///
+    /// This is the value checked against [Builder::size_limit].
+    pub fn compiled_size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the capture groups of the regular expression in order of their index.
+    ///
+    /// The first item is the implicit group of the whole match with index 0 and no name.
+    ///
+    /// # Example
+    /// ```rust
+    /// # fn main() -> Result<(), de_regex::Error> {
+    /// use std::collections::HashMap;
+    /// use de_regex::Pattern;
+    ///
+    /// let pattern: Pattern<HashMap<String, String>> = Pattern::new(r"^(?P<width>\d+)(x)(?P<height>\d+)$")?;
+    /// let groups: Vec<_> = pattern.groups().map(|g| (g.index(), g.name())).collect();
+    ///
+    /// assert_eq!(groups, vec![(0, None), (1, Some("width")), (2, None), (3, Some("height"))]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn groups(&self) -> impl Iterator<Item = Group<'_>> {
+        self.regex.capture_names().enumerate().map(|(index, name)| Group { index, name })
+    }
+
+    /// Returns the number of capture groups including the implicit group of the whole match
+    pub fn group_count(&self) -> usize {
+        self.regex.captures_len()
+    }
+
+    /// Returns the index of the group with the given name
+    pub fn group_index(&self, name: &str) -> Option<usize> {
+        self.regex.capture_names().position(|n| n == Some(name))
+    }
+
+    /// Deserialize an input string into a value of type `T`
+    pub fn from_str<'de>(&self, input: &'de str) -> std::result::Result<T, Error> where T: Deserialize<'de> {
+        let mut deserializer = de::Deserializer::new(input, &self.regex, &self.options);
+        T::deserialize(&mut deserializer)
+    }
+
+    /// Deserialize all matches in an input string into values of type `T`.
+    ///
+    /// See [from_str_all](crate::from_str_all) for details.
+    pub fn from_str_all<'de>(&self, input: &'de str) -> std::result::Result<Vec<T>, Error> where T: Deserialize<'de> {
+        self.from_str_iter(input).collect()
+    }
+
+    /// Returns an iterator that lazily deserializes all matches in an input string into values of type `T`.
+    ///
+    /// See [from_str_iter](crate::from_str_iter) for details.
+    pub fn from_str_iter<'de>(&self, input: &'de str) -> Iter<'de, T> where T: Deserialize<'de> {
+        Iter::new(input, self.regex.clone(), self.options.clone())
+    }
+
+    /// Deserialize an input string into a value of type `T` or return `None` if the input doesn't match.
+    ///
+    /// See [try_from_str](crate::try_from_str) for details.
+    pub fn try_from_str<'de>(&self, input: &'de str) -> std::result::Result<Option<T>, Error> where T: Deserialize<'de> {
+        let mut deserializer = de::Deserializer::new(input, &self.regex, &self.options);
+        if deserializer.try_match()? {
+            T::deserialize(&mut deserializer).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Deserialize an owned input string into a value of type `T`
+    pub fn from_string(&self, input: impl Into<String>) -> std::result::Result<T, Error> where T: DeserializeOwned {
+        self.from_str(&input.into())
+    }
+
+    /// Match an input string once to deserialize the captures into several types.
+    ///
+    /// # Example
+    /// ```rust
+    /// # fn main() -> Result<(), de_regex::Error> {
+    /// use serde::Deserialize;
+    /// use de_regex::Pattern;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Common {
+    ///     level: String,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Record {
+    ///     level: String,
+    ///     message: String,
+    /// }
+    ///
+    /// let pattern: Pattern<Record> = Pattern::new(r"^(?P<level>\w+): (?P<message>.*)$")?;
+    /// let captures = pattern.captures("WARN: disk full")?;
+    ///
+    /// let common: Common = captures.deserialize()?;
+    /// let record: Record = captures.deserialize()?;
+    ///
+    /// assert_eq!(common.level, "WARN");
+    /// assert_eq!(record.message, "disk full");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn captures<'de>(&self, input: &'de str) -> std::result::Result<Captures<'_, 'de>, Error> {
+        let mut deserializer = de::Deserializer::new(input, &self.regex, &self.options);
+        if !deserializer.try_match()? {
+            return Err(Error::no_match(self.regex.capture_names(), &[]));
+        }
+
+        Ok(Captures {
+            input,
+            regex: &self.regex,
+            options: &self.options,
+            captures: deserializer.into_captures().expect("Input was matched"),
+        })
+    }
+
+    /// Deserialize an input string and render a table of all groups with their raw and converted values.
+    ///
+    /// The output is stable and meant to be used in snapshot tests. See [render](crate::render) for an example.
+    pub fn render<'de>(&self, input: &'de str) -> std::result::Result<String, Error> where T: Deserialize<'de> {
+        render::render::<T>(input, &self.regex, &self.options)
+    }
+}
+
+/// The captures of a single match of a [Pattern] that can be deserialized into several types
+pub struct Captures<'a, 'de> {
+    input: &'de str,
+    regex: &'a Regex,
+    options: &'a Options,
+    captures: de::Locations,
+}
+
+impl<'de> Captures<'_, 'de> {
+    /// Deserialize the captures into a value of type `U`
+    pub fn deserialize<U>(&self) -> std::result::Result<U, Error> where U: Deserialize<'de> {
+        let mut deserializer = de::Deserializer::new(self.input, self.regex, self.options)
+            .with_captures(self.captures.clone());
+        U::deserialize(&mut deserializer)
+    }
+}
+
+/// A capture group of a [Pattern]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Group<'a> {
+    index: usize,
+    name: Option<&'a str>,
+}
+
+impl<'a> Group<'a> {
+    /// Returns the index of the group
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the name of the group or `None` for unnamed groups
+    pub fn name(&self) -> Option<&'a str> {
+        self.name
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::builder::Builder;
+use crate::error::*;
+use crate::pattern::Pattern;
+
+/// A cache of compiled patterns namespaced by tenant.
+///
+/// Every tenant gets its own set of patterns limited by the quotas of the registry.
+/// If a newly compiled pattern exceeds a quota, the least recently used patterns
+/// of the same tenant are evicted. Patterns of other tenants are never affected.
+///
+/// Patterns are returned as [Arc], so they can be used without holding a lock on a shared registry.
+/// Options like a [time budget](Builder::timeout) are set with a [template](PatternRegistry::template).
+///
+/// # Example
+/// ```rust
+/// # fn main() -> Result<(), de_regex::Error> {
+/// use std::collections::HashMap;
+/// use de_regex::PatternRegistry;
+///
+/// let mut registry: PatternRegistry<HashMap<String, String>> = PatternRegistry::new();
+/// registry.max_patterns(100);
+///
+/// let values = registry.get("tenant-a", r"^(?P<key>\w+)=(?P<value>\w+)$")?.from_str("foo=bar")?;
+///
+/// assert_eq!(values["key"], "foo");
+/// assert_eq!(values["value"], "bar");
+/// assert_eq!(registry.len("tenant-a"), 1);
+/// assert_eq!(registry.len("tenant-b"), 0);
+/// # Ok(())
+/// # }
+/// ```
+pub struct PatternRegistry<T> {
+    tenants: HashMap<String, Tenant<T>>,
+    template: Builder,
+    max_patterns: Option<usize>,
+    max_size: Option<usize>,
+    clock: u64,
+}
+
+struct Tenant<T> {
+    patterns: HashMap<String, Entry<T>>,
+    size: usize,
+}
+
+struct Entry<T> {
+    pattern: Arc<Pattern<T>>,
+    last_used: u64,
+}
+
+impl<T> PatternRegistry<T> {
+    /// Create a new registry without any quotas
+    pub fn new() -> PatternRegistry<T> {
+        PatternRegistry {
+            tenants: HashMap::new(),
+            template: Builder::new(""),
+            max_patterns: None,
+            max_size: None,
+            clock: 0,
+        }
+    }
+
+    /// Set a builder whose options are used to compile every pattern.
+    ///
+    /// The regular expression of the template is replaced by the requested pattern.
+    /// The [size limit](Builder::size_limit) is overridden by [max_size](PatternRegistry::max_size) if set.
+    ///
+    /// # Example
+    /// ```rust
+    /// # fn main() -> Result<(), de_regex::Error> {
+    /// use std::collections::HashMap;
+    /// use std::time::Duration;
+    /// use de_regex::{Builder, PatternRegistry};
+    ///
+    /// let mut registry: PatternRegistry<HashMap<String, String>> = PatternRegistry::new();
+    /// registry.template(Builder::new("").timeout(Duration::from_millis(10)));
+    ///
+    /// let pattern = registry.get("tenant-a", r"^(?P<key>\w+)=(?P<value>\w+)$")?;
+    /// assert_eq!(pattern.from_str("foo=bar")?["value"], "bar");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn template(&mut self, builder: &Builder) -> &mut PatternRegistry<T> {
+        self.template = builder.clone();
+        self
+    }
+
+    /// Set the maximum number of patterns per tenant.
+    ///
+    /// With a quota of zero every pattern is rejected with [Error::TooManyPatterns].
+    pub fn max_patterns(&mut self, count: usize) -> &mut PatternRegistry<T> {
+        self.max_patterns = Some(count);
+        self
+    }
+
+    /// Set the maximum sum of [compiled sizes](Pattern::compiled_size) in bytes per tenant.
+    ///
+    /// A single pattern exceeding this quota is rejected with [Error::PatternTooBig].
+    pub fn max_size(&mut self, bytes: usize) -> &mut PatternRegistry<T> {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    /// Returns the compiled pattern of a tenant and compiles it if it isn't cached already
+    pub fn get(&mut self, tenant: &str, pattern: &str) -> std::result::Result<Arc<Pattern<T>>, Error> {
+        self.clock += 1;
+
+        let cached = self.tenants.get(tenant).is_some_and(|tenant| tenant.patterns.contains_key(pattern));
+
+        if !cached {
+            // Every other quota can be met by evicting older patterns
+            if let Some(limit) = self.max_patterns.filter(|max| *max == 0) {
+                return Err(Error::TooManyPatterns { limit });
+            }
+
+            let mut builder = self.template.clone();
+            builder.pattern(pattern);
+            if let Some(max_size) = self.max_size {
+                builder.size_limit(max_size);
+            }
+
+            let compiled = Arc::new(builder.build()?);
+
+            let tenant = self.tenants.entry(tenant.to_owned()).or_insert_with(|| Tenant {
+                patterns: HashMap::new(),
+                size: 0,
+            });
+
+            tenant.size += compiled.compiled_size();
+            tenant.patterns.insert(pattern.to_owned(), Entry { pattern: compiled, last_used: self.clock });
+            tenant.evict(pattern, self.max_patterns, self.max_size);
+        }
+
+        let entry = self.tenants.get_mut(tenant)
+            .and_then(|tenant| tenant.patterns.get_mut(pattern))
+            .expect("Pattern was inserted above");
+        entry.last_used = self.clock;
+        Ok(entry.pattern.clone())
+    }
+
+    /// Remove a single pattern of a tenant from the cache
+    pub fn remove(&mut self, tenant: &str, pattern: &str) {
+        if let Some(tenant) = self.tenants.get_mut(tenant) {
+            if let Some(entry) = tenant.patterns.remove(pattern) {
+                tenant.size -= entry.pattern.compiled_size();
+            }
+        }
+    }
+
+    /// Remove all patterns of a tenant
+    pub fn remove_tenant(&mut self, tenant: &str) {
+        self.tenants.remove(tenant);
+    }
+
+    /// Returns the number of cached patterns of a tenant
+    pub fn len(&self, tenant: &str) -> usize {
+        self.tenants.get(tenant).map_or(0, |tenant| tenant.patterns.len())
+    }
+
+    /// Returns true if no pattern of the tenant is cached
+    pub fn is_empty(&self, tenant: &str) -> bool {
+        self.len(tenant) == 0
+    }
+
+    /// Returns the sum of [compiled sizes](Pattern::compiled_size) of all cached patterns of a tenant
+    pub fn size(&self, tenant: &str) -> usize {
+        self.tenants.get(tenant).map_or(0, |tenant| tenant.size)
+    }
+}
+
+impl<T> Default for PatternRegistry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Tenant<T> {
+    /// Evict least recently used patterns until the quotas are met, keeping the pattern `keep`
+    fn evict(&mut self, keep: &str, max_patterns: Option<usize>, max_size: Option<usize>) {
+        while max_patterns.is_some_and(|max| self.patterns.len() > max)
+            || max_size.is_some_and(|max| self.size > max) {
+            let oldest = self.patterns.iter()
+                .filter(|(source, _)| source.as_str() != keep)
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(source, _)| source.clone());
+
+            match oldest {
+                Some(source) => {
+                    let entry = self.patterns.remove(&source).expect("Pattern exists");
+                    self.size -= entry.pattern.compiled_size();
+                }
+                None => break,
+            }
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::cell::RefCell;
+
+use serde::Deserialize;
+use regex::Regex;
+
+use crate::builder::Options;
+use crate::de;
+use crate::error::*;
+
+/// Collects the conversions of all values during deserialization
+#[derive(Default)]
+pub(crate) struct Trace {
+    records: RefCell<Vec<Record>>,
+}
+
+struct Record {
+    name: String,
+    type_name: &'static str,
+    value: String,
+}
+
+impl Trace {
+    pub fn record(&self, name: &str, type_name: &'static str, value: String) {
+        self.records.borrow_mut().push(Record {
+            name: name.to_owned(),
+            type_name,
+            value,
+        });
+    }
+}
+
+/// Deserialize the input and render a table of all groups with their raw and converted values.
+///
+/// Unnamed groups are listed by their index.
+pub(crate) fn render<'de, T>(input: &'de str, regex: &Regex, options: &Options) -> Result<String> where T: Deserialize<'de> {
+    let mut matcher = de::Deserializer::new(input, regex, options);
+    if !matcher.try_match()? {
+        return Err(Error::no_match(regex.capture_names(), &[]));
+    }
+    let caps = matcher.into_captures().expect("Input was matched");
+
+    let trace = Trace::default();
+    let mut deserializer = de::Deserializer::new(input, regex, options)
+        .with_captures(caps.clone())
+        .with_trace(&trace);
+    T::deserialize(&mut deserializer)?;
+
+    let records = trace.records.into_inner();
+
+    let mut rows = vec![[
+        "group".to_owned(),
+        "raw".to_owned(),
+        "type".to_owned(),
+        "value".to_owned(),
+    ]];
+
+    for (i, name) in regex.capture_names().enumerate().skip(1) {
+        let name = name.map_or_else(|| i.to_string(), str::to_owned);
+        let raw = caps[i].map_or_else(|| "-".to_owned(), |(start, end)| format!("{:?}", &input[start..end]));
+
+        let matching: Vec<_> = records.iter().filter(|r| r.name == name).collect();
+
+        // Every element of a split group is recorded on its own
+        let row = match matching.last() {
+            Some(record) if options.separators.contains_key(&name) => {
+                let values: Vec<_> = matching.iter().map(|r| r.value.as_str()).collect();
+                [name, raw, format!("[{}]", record.type_name), format!("[{}]", values.join(", "))]
+            }
+            Some(record) => [name, raw, record.type_name.to_owned(), record.value.clone()],
+            None => [name, raw, "-".to_owned(), "-".to_owned()],
+        };
+
+        rows.push(row);
+    }
+
+    let mut widths = [0; 4];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let separator: Vec<_> = widths.iter().map(|width| "-".repeat(*width)).collect();
+
+    let mut table = String::new();
+    for (i, row) in rows.iter().enumerate() {
+        table.push_str(&format_row(row, &widths));
+        table.push('\n');
+
+        if i == 0 {
+            table.push_str(&separator.join("-+-"));
+            table.push('\n');
+        }
+    }
+
+    Ok(table)
+}
+
+fn format_row(row: &[String; 4], widths: &[usize; 4]) -> String {
+    let cells: Vec<_> = row.iter().zip(widths).map(|(cell, width)| {
+        format!("{}{}", cell, " ".repeat(width - cell.chars().count()))
+    }).collect();
+
+    cells.join(" | ").trim_end().to_owned()
+}
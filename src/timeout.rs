@@ -0,0 +1,302 @@
+use std::ops::Range;
+use std::time::Instant;
+
+use regex_automata::hybrid::dfa::{Cache, DFA};
+use regex_automata::nfa::thompson::{self, NFA, State};
+use regex_automata::util::pool::Pool;
+use regex_automata::util::primitives::StateID;
+use regex_automata::{Anchored, Input, MatchKind};
+
+use crate::de::{check_deadline, Locations};
+use crate::error::*;
+
+// Number of bytes scanned between two checks of the deadline
+const CHUNK_SIZE: usize = 4096;
+
+type CachePool = Pool<Caches, Box<dyn Fn() -> Caches + Send + Sync>>;
+
+/// A regex search that checks a deadline while it scans the input.
+///
+/// The span of the leftmost match is searched byte by byte with a forward and a reverse lazy DFA.
+/// The groups are extracted afterwards by an anchored NFA simulation that is limited to this span.
+pub(crate) struct TimedRegex {
+    forward: DFA,
+    reverse: DFA,
+    caches: CachePool,
+    nfa: NFA,
+}
+
+struct Caches {
+    forward: Cache,
+    reverse: Cache,
+    current: Threads,
+    next: Threads,
+}
+
+impl TimedRegex {
+    pub fn new(pattern: &str, size_limit: usize) -> Result<TimedRegex> {
+        let thompson = thompson::Config::new().nfa_size_limit(Some(size_limit));
+
+        let forward = DFA::builder()
+            .thompson(thompson.clone())
+            .build(pattern)
+            .map_err(unsupported)?;
+
+        let reverse = DFA::builder()
+            .configure(DFA::config().match_kind(MatchKind::All))
+            .thompson(thompson.clone().reverse(true))
+            .build(pattern)
+            .map_err(unsupported)?;
+
+        let nfa = NFA::compiler()
+            .configure(thompson)
+            .build(pattern)
+            .map_err(unsupported)?;
+
+        let (f, r, n) = (forward.clone(), reverse.clone(), nfa.clone());
+        let caches = Pool::new(Box::new(move || Caches {
+            forward: f.create_cache(),
+            reverse: r.create_cache(),
+            current: Threads::new(&n),
+            next: Threads::new(&n),
+        }) as Box<_>);
+
+        Ok(TimedRegex {
+            forward,
+            reverse,
+            caches,
+            nfa,
+        })
+    }
+
+    /// Search the leftmost match starting at byte offset `start` and return the locations of all groups
+    pub fn captures_at(&self, input: &str, start: usize, deadline: Instant) -> Result<Option<Locations>> {
+        let haystack = input.as_bytes();
+        let mut caches = self.caches.get();
+
+        let end = match self.find_end(&mut caches.forward, haystack, start, deadline)? {
+            Some(end) => end,
+            None => return Ok(None),
+        };
+
+        let start = self.find_start(&mut caches.reverse, haystack, start, end, deadline)?;
+        drop(caches);
+
+        self.captures_in(input, start..end, deadline)
+    }
+
+    /// Returns the end of the leftmost-first match
+    fn find_end(&self, cache: &mut Cache, haystack: &[u8], start: usize, deadline: Instant) -> Result<Option<usize>> {
+        let mut sid = self.forward.start_state_forward(cache, &Input::new(haystack).range(start..)).map_err(unsupported)?;
+        let mut end = None;
+
+        for (at, &byte) in haystack.iter().enumerate().skip(start) {
+            if (at - start).is_multiple_of(CHUNK_SIZE) {
+                check_deadline(Some(deadline))?;
+            }
+
+            // Matches are delayed by one byte
+            sid = self.forward.next_state(cache, sid, byte).map_err(unsupported)?;
+            if sid.is_match() {
+                end = Some(at);
+            } else if sid.is_dead() {
+                return Ok(end);
+            }
+        }
+
+        sid = self.forward.next_eoi_state(cache, sid).map_err(unsupported)?;
+        if sid.is_match() {
+            end = Some(haystack.len());
+        }
+
+        Ok(end)
+    }
+
+    /// Returns the start of the longest match ending at `end` that doesn't start before `start`
+    fn find_start(&self, cache: &mut Cache, haystack: &[u8], start: usize, end: usize, deadline: Instant) -> Result<usize> {
+        let input = Input::new(haystack).range(start..end).anchored(Anchored::Yes);
+        let mut sid = self.reverse.start_state_reverse(cache, &input).map_err(unsupported)?;
+        let mut found = end;
+
+        for at in (start..end).rev() {
+            if (end - at).is_multiple_of(CHUNK_SIZE) {
+                check_deadline(Some(deadline))?;
+            }
+
+            sid = self.reverse.next_state(cache, sid, haystack[at]).map_err(unsupported)?;
+            if sid.is_match() {
+                found = at + 1;
+            } else if sid.is_dead() {
+                return Ok(found);
+            }
+        }
+
+        // The byte before the search start is needed for look-behind assertions
+        sid = match start.checked_sub(1) {
+            Some(before) => self.reverse.next_state(cache, sid, haystack[before]),
+            None => self.reverse.next_eoi_state(cache, sid),
+        }.map_err(unsupported)?;
+
+        if sid.is_match() {
+            found = start;
+        }
+
+        Ok(found)
+    }
+
+    /// Returns the groups of the leftmost-first match that starts at the start of `span` and doesn't exceed its end.
+    ///
+    /// This is a Pike VM that runs all threads of the NFA in lock step to check the deadline while it advances.
+    pub fn captures_in(&self, input: &str, span: Range<usize>, deadline: Instant) -> Result<Option<Locations>> {
+        let (haystack, start, end) = (input.as_bytes(), span.start, span.end);
+        let mut caches = self.caches.get();
+        let Caches { current, next, .. } = &mut *caches;
+        let mut slots = vec![None; self.nfa.group_info().slot_len()];
+        let mut stack = vec![];
+        let mut matched = None;
+
+        current.clear();
+        self.add_thread(current, &mut stack, &mut slots, haystack, start, self.nfa.start_anchored());
+
+        for at in start..=end {
+            if (at - start).is_multiple_of(CHUNK_SIZE) {
+                check_deadline(Some(deadline))?;
+            }
+
+            if current.is_empty() {
+                break;
+            }
+
+            next.clear();
+
+            for i in 0..current.len() {
+                let sid = current.states[i];
+
+                let target = match (self.nfa.state(sid), haystack.get(at).filter(|_| at < end)) {
+                    (State::Match { .. }, _) => {
+                        // Threads with a lower priority than a match are dropped
+                        matched = Some(current.slots(sid).to_vec());
+                        break;
+                    }
+                    (State::ByteRange { trans }, Some(&byte)) => Some(trans.next).filter(|_| trans.matches_byte(byte)),
+                    (State::Sparse(sparse), Some(&byte)) => sparse.matches_byte(byte),
+                    (State::Dense(dense), Some(&byte)) => dense.matches_byte(byte),
+                    _ => None,
+                };
+
+                if let Some(target) = target {
+                    slots.copy_from_slice(current.slots(sid));
+                    self.add_thread(next, &mut stack, &mut slots, haystack, at + 1, target);
+                }
+            }
+
+            std::mem::swap(current, next);
+        }
+
+        Ok(matched.map(|slots: Vec<Option<usize>>| {
+            slots.chunks(2).map(|span| Some((span[0]?, span[1]?))).collect()
+        }))
+    }
+
+    /// Add the thread for `sid` and all states reachable without consuming input in order of their priority
+    fn add_thread(&self, threads: &mut Threads, stack: &mut Vec<Frame>, slots: &mut [Option<usize>], haystack: &[u8], at: usize, sid: StateID) {
+        stack.push(Frame::Explore(sid));
+
+        while let Some(frame) = stack.pop() {
+            let mut sid = match frame {
+                Frame::Explore(sid) => sid,
+                Frame::Restore(slot, offset) => {
+                    slots[slot] = offset;
+                    continue;
+                }
+            };
+
+            while threads.insert(sid) {
+                sid = match self.nfa.state(sid) {
+                    State::Look { look, next } if self.nfa.look_matcher().matches(*look, haystack, at) => *next,
+                    State::Union { alternates } if !alternates.is_empty() => {
+                        stack.extend(alternates[1..].iter().rev().map(|&alt| Frame::Explore(alt)));
+                        alternates[0]
+                    }
+                    State::BinaryUnion { alt1, alt2 } => {
+                        stack.push(Frame::Explore(*alt2));
+                        *alt1
+                    }
+                    State::Capture { next, slot, .. } => {
+                        stack.push(Frame::Restore(slot.as_usize(), slots[slot.as_usize()]));
+                        slots[slot.as_usize()] = Some(at);
+                        *next
+                    }
+                    _ => {
+                        threads.set_slots(sid, slots);
+                        break;
+                    }
+                };
+            }
+        }
+    }
+}
+
+enum Frame {
+    Explore(StateID),
+    Restore(usize, Option<usize>),
+}
+
+/// An ordered set of NFA states with the group offsets of each state
+struct Threads {
+    states: Vec<StateID>,
+    index: Vec<usize>,
+    slots: Vec<Option<usize>>,
+    slot_len: usize,
+}
+
+impl Threads {
+    fn new(nfa: &NFA) -> Threads {
+        let slot_len = nfa.group_info().slot_len();
+
+        Threads {
+            states: Vec::with_capacity(nfa.states().len()),
+            index: vec![0; nfa.states().len()],
+            slots: vec![None; nfa.states().len() * slot_len],
+            slot_len,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    fn clear(&mut self) {
+        self.states.clear();
+    }
+
+    /// Returns false if the state is already part of the set
+    fn insert(&mut self, sid: StateID) -> bool {
+        let index = self.index[sid.as_usize()];
+        if index < self.states.len() && self.states[index] == sid {
+            return false;
+        }
+
+        self.index[sid.as_usize()] = self.states.len();
+        self.states.push(sid);
+        true
+    }
+
+    fn slots(&self, sid: StateID) -> &[Option<usize>] {
+        let start = sid.as_usize() * self.slot_len;
+        &self.slots[start..start + self.slot_len]
+    }
+
+    fn set_slots(&mut self, sid: StateID, slots: &[Option<usize>]) {
+        let start = sid.as_usize() * self.slot_len;
+        self.slots[start..start + self.slot_len].copy_from_slice(slots);
+    }
+}
+
+fn unsupported(err: impl std::fmt::Display) -> Error {
+    Error::TimeoutUnsupported(err.to_string())
+}